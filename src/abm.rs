@@ -1,4 +1,16 @@
+use crate::convergence::Discretization;
+use crate::paths::uniform_grid;
+use crate::qmc::{BrownianBuilder, PathConstruction, Sobol};
+use crate::stream::{PathIter, StepIter};
+use crate::{
+    engine, normal, AbmError, Coefficient, Estimate, PathMatrix, Scheme, StochasticProcess,
+};
 use rand::Rng;
+use rand_distr::StandardNormal;
+
+/// Mixed into the seed of `refine`, so that its streams differ from the ones
+/// that generated the coarse paths.
+const REFINE_SALT: u64 = 0x5851_f42d_4c95_7f2d;
 
 /// The Arithmetic Brownian Motion (ABM) model simulates the price movement
 /// of an asset over time using the following formula:
@@ -8,14 +20,29 @@ use rand::Rng;
 /// Where:
 /// - `mu` is the drift (expected return)
 /// - `sigma` is the volatility (standard deviation of returns)
-/// - `d_w` is a Wiener process increment (Brownian motion)
+/// - `d_w` is a Wiener process increment (Brownian motion), distributed as N(0, dt)
+///
+/// `mu` and `sigma` may also be deterministic functions of time, see [`Coefficient`].
+#[derive(Debug, Clone)]
 pub struct ArithmeticBrownianMotion {
-    pub mu: f64,
-    pub sigma: f64,
+    pub mu: Coefficient,
+    pub sigma: Coefficient,
     pub n_paths: usize,
     pub n_steps: usize,
     pub t_end: f64,
     pub s_0: f64,
+    /// Optional seed of the per-path random streams, see
+    /// [reproducibility](crate#reproducibility).
+    pub seed: Option<u64>,
+    /// The time-stepping scheme, `Scheme::Exact` by default.
+    pub scheme: Scheme,
+    /// Optional explicit observation times. When set, paths are simulated on
+    /// this grid instead of the uniform one, and `n_steps` and `t_end` must
+    /// describe it (`validate` checks that they do).
+    pub time_grid: Option<Vec<f64>>,
+    /// Whether paths are generated in antithetic pairs: paths `2k` and `2k + 1`
+    /// share one set of normal draws with opposite signs.
+    pub antithetic: bool,
 }
 
 impl ArithmeticBrownianMotion {
@@ -32,45 +59,927 @@ impl ArithmeticBrownianMotion {
     ///
     /// # Returns
     ///
-    /// A new instance of `ArithmeticBrownianMotion`.
+    /// A new instance of `ArithmeticBrownianMotion`. The parameters are not
+    /// checked; use `try_new` to reject invalid ones up front.
     pub fn new(mu: f64, sigma: f64, n_paths: usize, n_steps: usize, t_end: f64, s_0: f64) -> Self {
         Self {
-            mu,
-            sigma,
+            mu: Coefficient::Constant(mu),
+            sigma: Coefficient::Constant(sigma),
             n_paths,
             n_steps,
             t_end,
             s_0,
+            seed: None,
+            scheme: Scheme::default(),
+            time_grid: None,
+            antithetic: false,
+        }
+    }
+
+    /// Returns a builder with named setters, see [`AbmBuilder`].
+    pub fn builder() -> AbmBuilder {
+        AbmBuilder::default()
+    }
+
+    /// Creates a new instance of the Arithmetic Brownian Motion model,
+    /// validating the parameters.
+    ///
+    /// Takes the same arguments as `new`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AbmError` if any of `mu`, `sigma`, `t_end` or `s_0` is not
+    /// finite, if `sigma` is negative, if `n_steps` is zero or if `t_end` is not
+    /// positive. For piecewise-constant coefficients the layout and every piece
+    /// are checked; closures cannot be inspected and are accepted as they are.
+    pub fn try_new(
+        mu: f64,
+        sigma: f64,
+        n_paths: usize,
+        n_steps: usize,
+        t_end: f64,
+        s_0: f64,
+    ) -> Result<Self, AbmError> {
+        let abm = Self::new(mu, sigma, n_paths, n_steps, t_end, s_0);
+        abm.validate()?;
+        Ok(abm)
+    }
+
+    /// Checks that the current parameters describe a well-defined simulation.
+    ///
+    /// # Errors
+    ///
+    /// See `try_new`.
+    pub fn validate(&self) -> Result<(), AbmError> {
+        if let Some(grid) = &self.time_grid {
+            validate_time_grid(grid)?;
+            if grid.len() != self.n_steps + 1 {
+                return Err(AbmError::InvalidTimeGrid(format!(
+                    "{} points do not match n_steps = {}",
+                    grid.len(),
+                    self.n_steps
+                )));
+            }
+            if grid[grid.len() - 1] != self.t_end {
+                return Err(AbmError::InvalidTimeGrid(format!(
+                    "ends at {} but t_end = {}",
+                    grid[grid.len() - 1],
+                    self.t_end
+                )));
+            }
+        }
+        self.mu.validate_layout()?;
+        self.sigma.validate_layout()?;
+        let coefficients = self.mu.known_values().iter().map(|&v| ("mu", v));
+        let volatilities = self.sigma.known_values().iter().map(|&v| ("sigma", v));
+        for (name, value) in coefficients
+            .chain(volatilities)
+            .chain([("t_end", self.t_end), ("s_0", self.s_0)])
+        {
+            if !value.is_finite() {
+                return Err(AbmError::NonFinite { name, value });
+            }
+        }
+        if let Some(&sigma) = self.sigma.known_values().iter().find(|&&v| v < 0.0) {
+            return Err(AbmError::NegativeVolatility(sigma));
+        }
+        if self.n_steps == 0 {
+            return Err(AbmError::ZeroSteps);
+        }
+        if self.antithetic && !self.n_paths.is_multiple_of(2) {
+            return Err(AbmError::OddAntitheticPaths(self.n_paths));
+        }
+        if self.t_end <= 0.0 {
+            return Err(AbmError::NonPositiveHorizon(self.t_end));
+        }
+        Ok(())
+    }
+
+    /// Panics with a descriptive message if the parameters are invalid.
+    fn assert_valid(&self) {
+        if let Err(err) = self.validate() {
+            panic!("invalid ArithmeticBrownianMotion: {err}");
         }
     }
 
-    /// Simulates the asset price paths using the Euler-Maruyama method.
+    /// Fixes the seed used by `simulate`, so that repeated runs produce
+    /// bit-identical paths.
+    ///
+    /// The seed drives ChaCha8 generators, whose output streams are portable
+    /// across platforms.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Selects the time-stepping scheme.
+    pub fn with_scheme(mut self, scheme: Scheme) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
+    /// Simulates on an explicit grid of observation times, such as fixing
+    /// dates, instead of `n_steps` equal steps up to `t_end`.
+    ///
+    /// The grid must start at 0 and be strictly increasing; `n_steps` and
+    /// `t_end` are updated to match it. Each step uses its own `dt`.
+    pub fn with_time_grid(mut self, times: Vec<f64>) -> Self {
+        self.n_steps = times.len().saturating_sub(1);
+        self.t_end = times.last().copied().unwrap_or(f64::NAN);
+        self.time_grid = Some(times);
+        self
+    }
+
+    /// Generates paths in antithetic pairs: each set of normal draws `Z` drives
+    /// one path and its mirror image driven by `-Z`, halving the number of
+    /// random draws and reducing the variance of estimates of monotone
+    /// payoffs. `n_paths` must be even.
+    ///
+    /// The returned `PathMatrix` is flagged as antithetic so that
+    /// [`PathMatrix::estimate`] computes standard errors from pair averages.
+    pub fn with_antithetic(mut self, antithetic: bool) -> Self {
+        self.antithetic = antithetic;
+        self
+    }
+
+    /// The observation times of every simulated path: the explicit grid if
+    /// one is set, otherwise `n_steps` equal steps from 0 to `t_end`.
+    pub fn time_points(&self) -> Vec<f64> {
+        match &self.time_grid {
+            Some(grid) => grid.clone(),
+            None => uniform_grid(self.n_steps, self.t_end),
+        }
+    }
+
+    /// Simulates the asset price paths with the configured scheme.
+    ///
+    /// The exact scheme draws each step from its Gaussian transition
+    ///
+    /// S(u) ~ N(S(t) + int_t^u mu(s) ds, int_t^u sigma(s)^2 ds)
+    ///
+    /// so it has no discretization error even when the coefficients vary
+    /// within a step. Euler-Maruyama freezes `mu` and `sigma` at the start of
+    /// each step; with constant coefficients both schemes coincide.
     ///
     /// # Returns
     ///
-    /// A 2D vector where each inner vector represents a simulated path of asset prices.
+    /// A `PathMatrix` whose rows are the simulated paths of asset prices.
+    ///
+    /// Each path has `n_steps + 1` values, including the initial value `s_0`,
+    /// observed at `time_points()`, which the matrix carries along.
+    /// If `seed` is set the result is reproducible, otherwise a fresh seed is
+    /// drawn from the thread-local generator.
+    ///
+    /// Path `i` is driven by its own random stream derived from the seed, so
+    /// with the `parallel` feature the paths are generated across threads and
+    /// the result is identical whatever the number of threads. In antithetic
+    /// mode, pair `k` shares stream `k`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`); use `try_simulate`
+    /// to handle this as an error instead.
+    pub fn simulate(&self) -> PathMatrix {
+        self.assert_valid();
+        let mut paths = PathMatrix::new(self.time_points(), self.n_paths, self.s_0);
+        let increments = self.increments(paths.times());
+        let seed = engine::base_seed(self.seed);
+        if self.antithetic {
+            let pair_len = 2 * paths.n_times();
+            engine::fill_rows(paths.as_mut_slice(), pair_len, seed, |pair, rng| {
+                fill_antithetic_pair(pair, self.s_0, &increments, rng)
+            });
+            return paths.with_antithetic(true);
+        }
+        engine::fill_paths(&mut paths, seed, |path, rng| {
+            fill_path(path, self.s_0, &increments, rng)
+        });
+        paths
+    }
+
+    /// Simulates the asset price paths like `simulate`, returning an error
+    /// instead of panicking when the parameters are invalid.
+    ///
+    /// # Errors
     ///
-    /// Each path has `n_steps + 1` values, including the initial value `s_0`.
-    pub fn simulate(&self) -> Vec<Vec<f64>> {
-        let dt = self.t_end / self.n_steps as f64; // Time step size
-        let mut rng = rand::thread_rng(); // Random number generator
-        let mut paths = vec![vec![self.s_0; self.n_steps + 1]; self.n_paths]; // Initialize paths
+    /// Returns the `AbmError` reported by `validate`.
+    pub fn try_simulate(&self) -> Result<PathMatrix, AbmError> {
+        self.validate()?;
+        Ok(self.simulate())
+    }
 
-        // Simulate each path
-        for i in 0..self.n_paths {
-            for j in 1..=self.n_steps {
-                let d_w = rng.gen::<f64>() * dt.sqrt(); // Corrected to snake_case
-                paths[i][j] = paths[i][j - 1] + self.mu * dt + self.sigma * d_w; // Euler-Maruyama update
+    /// Simulates the asset price paths using a caller-supplied random number generator.
+    ///
+    /// The `seed` field is ignored; the paths are fully determined by the state of `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator used to draw the Wiener increments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn simulate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> PathMatrix {
+        self.assert_valid();
+        let mut paths = PathMatrix::new(self.time_points(), self.n_paths, self.s_0);
+        let increments = self.increments(paths.times());
+        if self.antithetic {
+            let pair_len = 2 * paths.n_times();
+            for pair in paths.as_mut_slice().chunks_exact_mut(pair_len) {
+                fill_antithetic_pair(pair, self.s_0, &increments, rng);
             }
+            return paths.with_antithetic(true);
         }
+        for path in paths.iter_mut() {
+            fill_path(path, self.s_0, &increments, rng);
+        }
+        paths
+    }
+
+    /// Iterates over the paths one at a time, without storing them all.
+    ///
+    /// With a seed the paths are exactly the rows of `simulate()`; without
+    /// one a fresh seed is drawn when the iterator is created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn paths(&self) -> PathIter {
+        self.assert_valid();
+        PathIter::new(
+            self.increments(&self.time_points()),
+            self.s_0,
+            engine::base_seed(self.seed),
+            self.n_paths,
+            self.antithetic,
+        )
+    }
+
+    /// Iterates over time steps, yielding `(t, values)` with the value of
+    /// every path at each time of `time_points()`.
+    ///
+    /// Memory is proportional to `n_paths` rather than
+    /// `n_paths * (n_steps + 1)`. With a seed the cross-sections are exactly
+    /// the columns of `simulate()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn steps(&self) -> StepIter {
+        self.assert_valid();
+        let times = self.time_points();
+        StepIter::new(
+            times.clone(),
+            self.increments(&times),
+            self.s_0,
+            engine::base_seed(self.seed),
+            self.n_paths,
+            self.antithetic,
+        )
+    }
 
+    /// Samples only the terminal values `S(t_end)`, without storing paths.
+    ///
+    /// Draws each value directly from its Gaussian law
+    ///
+    /// S(t_end) ~ N(s_0 + int_0^T mu(t) dt, int_0^T sigma(t)^2 dt)
+    ///
+    /// (with the integrals replaced by the scheme's step sums under
+    /// Euler-Maruyama), so memory is one `f64` per scenario. Value `i` uses
+    /// the random stream of path `i`, with antithetic pairs when enabled,
+    /// but the draws differ from those of `simulate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn simulate_terminal(&self) -> Vec<f64> {
+        self.assert_valid();
+        let (drift, variance) = self
+            .increments(&self.time_points())
+            .iter()
+            .fold((0.0, 0.0), |(m, v), &(dm, sd)| (m + dm, v + sd * sd));
+        let increment = (drift, variance.sqrt());
+        let mut values = vec![0.0; self.n_paths];
+        let seed = engine::base_seed(self.seed);
+        if self.antithetic {
+            engine::fill_rows(&mut values, 2, seed, |pair, rng| {
+                let z: f64 = rng.sample(StandardNormal);
+                pair[0] = advance(self.s_0, increment, z);
+                pair[1] = advance(self.s_0, increment, -z);
+            });
+        } else {
+            engine::fill_rows(&mut values, 1, seed, |value, rng| {
+                value[0] = advance(self.s_0, increment, rng.sample(StandardNormal));
+            });
+        }
+        values
+    }
+
+    /// Simulates the paths from scrambled Sobol points instead of
+    /// pseudo-random numbers (quasi-Monte Carlo).
+    ///
+    /// Point `i` of an Owen-scrambled Sobol sequence in `n_steps` dimensions is
+    /// mapped to normal variates by the inverse normal CDF, and `construction`
+    /// turns them into the Brownian path driving path `i`. With
+    /// `PathConstruction::BrownianBridge` or `PathConstruction::Pca` the first
+    /// coordinates carry most of the variance, which is where Sobol points are
+    /// most uniform. Powers of two for `n_paths` keep the point set balanced.
+    ///
+    /// The first [`JOE_KUO_DIMENSIONS`](crate::qmc::JOE_KUO_DIMENSIONS)
+    /// coordinates use Joe-Kuo direction numbers and later ones the hashed
+    /// numbers of [`Sobol::extended`]. Under the bridge and PCA constructions
+    /// those trailing coordinates only drive low-variance detail, so long grids
+    /// such as 252 daily steps keep the benefit of QMC; with
+    /// `PathConstruction::Incremental` every step weighs the same.
+    ///
+    /// The scrambling is keyed by `seed` (or a fresh seed); `antithetic` is
+    /// ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn simulate_qmc(&self, construction: PathConstruction) -> PathMatrix {
+        self.assert_valid();
+        let mut paths = PathMatrix::new(self.time_points(), self.n_paths, self.s_0);
+        self.fill_qmc(&mut paths, construction, engine::base_seed(self.seed));
         paths
     }
+
+    /// Randomized quasi-Monte Carlo estimate of `E[payoff(path)]`.
+    ///
+    /// Runs `simulate_qmc` with `n_randomizations` independent scramblings of
+    /// the Sobol points; the estimate is the average of the per-scrambling
+    /// means and its standard error comes from their spread, so
+    /// `n_samples` is `n_randomizations`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `simulate_qmc`.
+    pub fn rqmc_estimate<F: Fn(&[f64]) -> f64>(
+        &self,
+        construction: PathConstruction,
+        n_randomizations: usize,
+        payoff: F,
+    ) -> Estimate {
+        self.assert_valid();
+        let seed = engine::base_seed(self.seed);
+        let mut paths = PathMatrix::new(self.time_points(), self.n_paths, self.s_0);
+        let means: Vec<f64> = (0..n_randomizations as u64)
+            .map(|r| {
+                self.fill_qmc(&mut paths, construction, seed.wrapping_add(r));
+                paths.estimate(&payoff).mean
+            })
+            .collect();
+        Estimate::from_samples(&means)
+    }
+
+    /// Fills `paths` from the Sobol sequence scrambled with `seed`.
+    fn fill_qmc(&self, paths: &mut PathMatrix, construction: PathConstruction, seed: u64) {
+        let times = paths.times().to_vec();
+        let increments = self.increments(&times);
+        let builder = BrownianBuilder::new(construction, &times);
+        let mut sobol = Sobol::extended(self.n_steps).with_scrambling(seed);
+        let (mut z, mut w) = (vec![0.0; self.n_steps], vec![0.0; self.n_steps]);
+
+        for path in paths.iter_mut() {
+            sobol.next_point(&mut z);
+            for z in z.iter_mut() {
+                *z = normal::inverse_cdf(*z);
+            }
+            builder.build(&z, &mut w);
+            path[0] = self.s_0;
+            let mut previous = 0.0;
+            for (j, (&increment, &w)) in increments.iter().zip(&w).enumerate() {
+                // The standardized Brownian increment over step j.
+                let dz = (w - previous) / (times[j + 1] - times[j]).sqrt();
+                path[j + 1] = advance(path[j], increment, dz);
+                previous = w;
+            }
+        }
+    }
+
+    /// Simulates paths pinned to `terminal` at `t_end` (Brownian bridges).
+    ///
+    /// Conditionally on `S(t_a) = x_a` and `S(t_b) = x_b`, the value at
+    /// `t_a < t < t_b` is Gaussian with
+    ///
+    /// mean = x_a + M(t_a, t) + w * (x_b - x_a - M(t_a, t_b)),  w = V(t_a, t) / V(t_a, t_b)
+    ///
+    /// variance = V(t_a, t) * V(t, t_b) / V(t_a, t_b)
+    ///
+    /// where `M(s, u) = int_s^u mu` and `V(s, u) = int_s^u sigma^2`. Each step
+    /// samples this distribution from the previous point to the terminal
+    /// one, so the paths are exact for time-dependent coefficients too, and
+    /// a constant drift has no effect on them.
+    ///
+    /// Path `i` is driven by its own random stream, as in `simulate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`) or `terminal` is
+    /// not finite.
+    pub fn simulate_bridge(&self, terminal: f64) -> PathMatrix {
+        self.assert_valid();
+        assert!(
+            terminal.is_finite(),
+            "terminal value must be finite, got {terminal}"
+        );
+        let mut paths = PathMatrix::new(self.time_points(), self.n_paths, self.s_0);
+        let times = paths.times().to_vec();
+        let end = (self.t_end, terminal);
+        engine::fill_paths(&mut paths, engine::base_seed(self.seed), |path, rng| {
+            let last = path.len() - 1;
+            for j in 1..last {
+                let z: f64 = rng.sample(StandardNormal);
+                path[j] = self.bridge_value((times[j - 1], path[j - 1]), end, times[j], z);
+            }
+            path[last] = terminal;
+        });
+        paths
+    }
+
+    /// Refines `coarse` paths onto the finer grid `fine_times`, filling each
+    /// new point from the Brownian bridge between its neighbours.
+    ///
+    /// The coarse values are kept, and refining paths of this model gives the
+    /// same law as simulating them directly on the fine grid. `fine_times`
+    /// must start at 0, contain every time of the coarse grid, which must
+    /// itself start at 0, and end at the last coarse time. Path `i` is driven
+    /// by its own random stream, derived from the seed separately from those
+    /// of `simulate` so that refining the model's own paths does not reuse the
+    /// draws that produced them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of [`validate`](Self::validate) if the parameters are
+    /// invalid, and `AbmError::InvalidTimeGrid` if either grid is not valid or
+    /// `fine_times` misses a coarse time or extends past the last one.
+    pub fn refine(&self, coarse: &PathMatrix, fine_times: &[f64]) -> Result<PathMatrix, AbmError> {
+        self.validate()?;
+        validate_time_grid(coarse.times())?;
+        validate_time_grid(fine_times)?;
+        let (horizon, fine_end) = (
+            coarse.times()[coarse.n_times() - 1],
+            fine_times[fine_times.len() - 1],
+        );
+        if fine_end > horizon {
+            return Err(AbmError::InvalidTimeGrid(format!(
+                "fine grid ends at {fine_end}, after the coarse horizon {horizon}"
+            )));
+        }
+        // Index in `fine_times` of each coarse time.
+        let knots = coarse
+            .times()
+            .iter()
+            .map(|t| {
+                fine_times.iter().position(|u| u == t).ok_or_else(|| {
+                    AbmError::InvalidTimeGrid(format!("fine grid misses coarse time {t}"))
+                })
+            })
+            .collect::<Result<Vec<usize>, AbmError>>()?;
+
+        let seed = engine::base_seed(self.seed);
+        let mut fine = PathMatrix::new(fine_times.to_vec(), coarse.n_paths(), self.s_0);
+        for (i, (path, coarse_path)) in fine.iter_mut().zip(coarse).enumerate() {
+            let mut rng = engine::path_rng(seed ^ REFINE_SALT, i);
+            path[knots[0]] = coarse_path[0];
+            for (k, knot) in knots.windows(2).enumerate() {
+                let end = (fine_times[knot[1]], coarse_path[k + 1]);
+                for j in knot[0] + 1..knot[1] {
+                    let z: f64 = rng.sample(StandardNormal);
+                    let start = (fine_times[j - 1], path[j - 1]);
+                    path[j] = self.bridge_value(start, end, fine_times[j], z);
+                }
+                path[knot[1]] = coarse_path[k + 1];
+            }
+        }
+        Ok(fine)
+    }
+
+    /// A draw of `S(t)` given `S(t_a) = x_a` and `S(t_b) = x_b`, from the
+    /// standard normal variate `z`.
+    fn bridge_value(&self, (t_a, x_a): (f64, f64), (t_b, x_b): (f64, f64), t: f64, z: f64) -> f64 {
+        let var_total = self.sigma.integral_of_square(t_a, t_b);
+        let var_left = self.sigma.integral_of_square(t_a, t);
+        let drift_left = self.mu.integral(t_a, t);
+        let drift_total = self.mu.integral(t_a, t_b);
+        // With no variance over the interval the path is deterministic; fall
+        // back to time weights so that inconsistent endpoints still interpolate.
+        let (w, var) = if var_total > 0.0 {
+            let w = var_left / var_total;
+            (w, (var_left * (1.0 - w)).max(0.0))
+        } else {
+            ((t - t_a) / (t_b - t_a), 0.0)
+        };
+        x_a + drift_left + w * (x_b - x_a - drift_total) + var.sqrt() * z
+    }
+
+    /// The analytic mean of S(t): `s_0 + int_0^t mu(s) ds`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t < 0`.
+    pub fn mean(&self, t: f64) -> f64 {
+        assert_non_negative_time(t);
+        self.s_0 + self.mu.integral(0.0, t)
+    }
+
+    /// The analytic variance of S(t): `int_0^t sigma(s)^2 ds`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t < 0`.
+    pub fn variance(&self, t: f64) -> f64 {
+        assert_non_negative_time(t);
+        self.sigma.integral_of_square(0.0, t)
+    }
+
+    /// The analytic standard deviation of S(t).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t < 0`.
+    pub fn std_dev(&self, t: f64) -> f64 {
+        self.variance(t).sqrt()
+    }
+
+    /// The analytic covariance of S(s) and S(t), the variance at the earlier
+    /// of the two times, since increments over disjoint intervals are
+    /// independent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s < 0` or `t < 0`.
+    pub fn covariance(&self, s: f64, t: f64) -> f64 {
+        assert_non_negative_time(s);
+        assert_non_negative_time(t);
+        self.variance(s.min(t))
+    }
+
+    /// The density of S(t) at `x`.
+    ///
+    /// S(t) is Gaussian with the analytic mean and variance. At times with
+    /// zero variance, such as `t = 0`, the distribution is a point mass and
+    /// the density is infinite at the mean and zero elsewhere.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t < 0`.
+    pub fn density(&self, t: f64, x: f64) -> f64 {
+        self.transition_density(0.0, self.s_0, t, x)
+    }
+
+    /// The density of S(t) at `y` given S(s) = `x`, for `s <= t`:
+    ///
+    /// S(t) | S(s) = x ~ N(x + int_s^t mu(u) du, int_s^t sigma(u)^2 du)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s > t`.
+    pub fn transition_density(&self, s: f64, x: f64, t: f64, y: f64) -> f64 {
+        let (mean, std_dev) = self.transition(s, x, t);
+        if std_dev == 0.0 {
+            return if y == mean { f64::INFINITY } else { 0.0 };
+        }
+        normal::pdf((y - mean) / std_dev) / std_dev
+    }
+
+    /// The cumulative distribution function of S(t), `P(S(t) <= x)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t < 0`.
+    pub fn cdf(&self, t: f64, x: f64) -> f64 {
+        let (mean, std_dev) = self.transition(0.0, self.s_0, t);
+        if std_dev == 0.0 {
+            return if x >= mean { 1.0 } else { 0.0 };
+        }
+        normal::cdf((x - mean) / std_dev)
+    }
+
+    /// The `p`-quantile of S(t), the inverse of `cdf`. Evaluated on
+    /// `time_points()` for a few levels it gives the bands of a fan chart.
+    ///
+    /// Returns minus or plus infinity at `p = 0` and `p = 1` (the mean if the
+    /// variance is zero), and NaN outside `[0, 1]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t < 0`.
+    pub fn quantile(&self, t: f64, p: f64) -> f64 {
+        let (mean, std_dev) = self.transition(0.0, self.s_0, t);
+        if std_dev == 0.0 && (0.0..=1.0).contains(&p) {
+            return mean;
+        }
+        mean + std_dev * normal::inverse_cdf(p)
+    }
+
+    /// The mean and standard deviation of S(t) given S(s) = `x`.
+    fn transition(&self, s: f64, x: f64, t: f64) -> (f64, f64) {
+        assert!(s <= t, "transition from time {s} to earlier time {t}");
+        (
+            x + self.mu.integral(s, t),
+            self.sigma.integral_of_square(s, t).sqrt(),
+        )
+    }
+
+    /// Simulates a single asset price path of `n_steps + 1` values. The
+    /// antithetic setting does not apply to single paths.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        self.assert_valid();
+        let times = self.time_points();
+        let increments = self.increments(&times);
+        let mut path = vec![self.s_0; times.len()];
+        fill_path(&mut path, self.s_0, &increments, rng);
+        path
+    }
+
+    /// The mean and standard deviation of the Gaussian increment over each
+    /// step of `times`, computed once and shared by all paths.
+    fn increments(&self, times: &[f64]) -> Vec<(f64, f64)> {
+        times
+            .windows(2)
+            .map(|w| {
+                let (t, u) = (w[0], w[1]);
+                match self.scheme {
+                    Scheme::Exact => (
+                        self.mu.integral(t, u),
+                        self.sigma.integral_of_square(t, u).sqrt(),
+                    ),
+                    // The diffusion does not depend on the state, so the
+                    // higher-order corrections vanish and all discretizations
+                    // reduce to Euler-Maruyama.
+                    Scheme::EulerMaruyama | Scheme::Milstein | Scheme::RungeKutta => {
+                        let dt = u - t;
+                        (self.mu.value(t) * dt, self.sigma.value(t) * dt.sqrt())
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// The value after a step from `x` with Gaussian increment `mean + std_dev * z`.
+pub(crate) fn advance(x: f64, (mean, std_dev): (f64, f64), z: f64) -> f64 {
+    x + mean + std_dev * z
+}
+
+/// Writes a path from `s_0` into `path`, advancing by `increments` with one
+/// normal draw per step.
+pub(crate) fn fill_path<R: Rng + ?Sized>(
+    path: &mut [f64],
+    s_0: f64,
+    increments: &[(f64, f64)],
+    rng: &mut R,
+) {
+    path[0] = s_0;
+    for (j, &increment) in (1..path.len()).zip(increments) {
+        path[j] = advance(path[j - 1], increment, rng.sample(StandardNormal));
+    }
+}
+
+/// Writes an antithetic pair of paths into the two halves of `pair`, the
+/// second advancing with the negated draws of the first.
+pub(crate) fn fill_antithetic_pair<R: Rng + ?Sized>(
+    pair: &mut [f64],
+    s_0: f64,
+    increments: &[(f64, f64)],
+    rng: &mut R,
+) {
+    let (path, mirror) = pair.split_at_mut(pair.len() / 2);
+    path[0] = s_0;
+    mirror[0] = s_0;
+    for (j, &increment) in (1..path.len()).zip(increments) {
+        let z: f64 = rng.sample(StandardNormal);
+        path[j] = advance(path[j - 1], increment, z);
+        mirror[j] = advance(mirror[j - 1], increment, -z);
+    }
+}
+
+/// Panics unless `t` is a valid time of the process.
+fn assert_non_negative_time(t: f64) {
+    assert!(t >= 0.0, "time must be non-negative, got {t}");
+}
+
+/// Checks that an explicit grid starts at 0, is strictly increasing and
+/// contains at least one step.
+fn validate_time_grid(grid: &[f64]) -> Result<(), AbmError> {
+    if grid.len() < 2 {
+        return Err(AbmError::InvalidTimeGrid(format!(
+            "need at least 2 points, got {}",
+            grid.len()
+        )));
+    }
+    if grid[0] != 0.0 {
+        return Err(AbmError::InvalidTimeGrid(format!(
+            "must start at 0, got {}",
+            grid[0]
+        )));
+    }
+    if grid.iter().any(|t| !t.is_finite()) || grid.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(AbmError::InvalidTimeGrid(
+            "times must be finite and strictly increasing".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Builder for [`ArithmeticBrownianMotion`] with named setters.
+///
+/// Unset parameters default to a standard Brownian motion on `[0, 1]`:
+/// `mu = 0`, `sigma = 1`, `s_0 = 0`, `t_end = 1`, with `n_steps = 100`,
+/// `n_paths = 1`, no seed and `Scheme::Exact`.
+///
+/// ```
+/// use stochastic_abm::ArithmeticBrownianMotion;
+///
+/// let abm = ArithmeticBrownianMotion::builder()
+///     .mu(0.05)
+///     .sigma(0.4)
+///     .s_0(200.0)
+///     .t_end(1.0)
+///     .n_steps(200)
+///     .n_paths(50)
+///     .seed(42)
+///     .build()
+///     .unwrap();
+/// assert_eq!(abm.simulate().len(), 50);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AbmBuilder {
+    mu: Coefficient,
+    sigma: Coefficient,
+    n_paths: usize,
+    n_steps: usize,
+    t_end: f64,
+    s_0: f64,
+    seed: Option<u64>,
+    scheme: Scheme,
+    time_grid: Option<Vec<f64>>,
+    antithetic: bool,
+}
+
+impl Default for AbmBuilder {
+    fn default() -> Self {
+        Self {
+            mu: Coefficient::Constant(0.0),
+            sigma: Coefficient::Constant(1.0),
+            n_paths: 1,
+            n_steps: 100,
+            t_end: 1.0,
+            s_0: 0.0,
+            seed: None,
+            scheme: Scheme::default(),
+            time_grid: None,
+            antithetic: false,
+        }
+    }
+}
+
+impl AbmBuilder {
+    /// The drift (mean) of the asset's returns, a constant or a [`Coefficient`].
+    pub fn mu(mut self, mu: impl Into<Coefficient>) -> Self {
+        self.mu = mu.into();
+        self
+    }
+
+    /// The volatility (standard deviation) of the asset's returns, a constant
+    /// or a [`Coefficient`].
+    pub fn sigma(mut self, sigma: impl Into<Coefficient>) -> Self {
+        self.sigma = sigma.into();
+        self
+    }
+
+    /// Number of simulated paths.
+    pub fn n_paths(mut self, n_paths: usize) -> Self {
+        self.n_paths = n_paths;
+        self
+    }
+
+    /// Number of steps in each path.
+    pub fn n_steps(mut self, n_steps: usize) -> Self {
+        self.n_steps = n_steps;
+        self
+    }
+
+    /// Total time of simulation.
+    pub fn t_end(mut self, t_end: f64) -> Self {
+        self.t_end = t_end;
+        self
+    }
+
+    /// Initial value of the asset (price at t=0).
+    pub fn s_0(mut self, s_0: f64) -> Self {
+        self.s_0 = s_0;
+        self
+    }
+
+    /// Seed for reproducible simulations.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// The time-stepping scheme.
+    pub fn scheme(mut self, scheme: Scheme) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
+    /// Explicit observation times, overriding `n_steps` and `t_end`. See
+    /// [`ArithmeticBrownianMotion::with_time_grid`].
+    pub fn time_grid(mut self, times: Vec<f64>) -> Self {
+        self.time_grid = Some(times);
+        self
+    }
+
+    /// Antithetic pair generation. See [`ArithmeticBrownianMotion::with_antithetic`].
+    pub fn antithetic(mut self, antithetic: bool) -> Self {
+        self.antithetic = antithetic;
+        self
+    }
+
+    /// Builds the model, validating the parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AbmError` under the same conditions as
+    /// [`ArithmeticBrownianMotion::try_new`].
+    pub fn build(self) -> Result<ArithmeticBrownianMotion, AbmError> {
+        let abm = ArithmeticBrownianMotion {
+            mu: self.mu,
+            sigma: self.sigma,
+            n_paths: self.n_paths,
+            n_steps: self.n_steps,
+            t_end: self.t_end,
+            s_0: self.s_0,
+            seed: self.seed,
+            scheme: self.scheme,
+            time_grid: None,
+            antithetic: self.antithetic,
+        };
+        let abm = match self.time_grid {
+            Some(times) => abm.with_time_grid(times),
+            None => abm,
+        };
+        abm.validate()?;
+        Ok(abm)
+    }
+}
+
+impl StochasticProcess for ArithmeticBrownianMotion {
+    fn drift(&self, t: f64, _x: f64) -> f64 {
+        self.mu.value(t)
+    }
+
+    fn diffusion(&self, t: f64, _x: f64) -> f64 {
+        self.sigma.value(t)
+    }
+
+    fn initial_value(&self) -> f64 {
+        self.s_0
+    }
+
+    fn time_horizon(&self) -> f64 {
+        self.t_end
+    }
+
+    fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        ArithmeticBrownianMotion::sample_path(self, rng)
+    }
+
+    fn simulate(&self) -> PathMatrix {
+        ArithmeticBrownianMotion::simulate(self)
+    }
+}
+
+impl Discretization for ArithmeticBrownianMotion {
+    /// Under `Scheme::Exact` the increment `dw` is rescaled to the integrated
+    /// volatility of the step, which is exact in law and pathwise for constant
+    /// volatility.
+    fn step(&self, t: f64, x: f64, dt: f64, dw: f64) -> f64 {
+        match self.scheme {
+            Scheme::Exact => {
+                let std_dev = self.sigma.integral_of_square(t, t + dt).sqrt();
+                x + self.mu.integral(t, t + dt) + std_dev * dw / dt.sqrt()
+            }
+            Scheme::EulerMaruyama | Scheme::Milstein | Scheme::RungeKutta => {
+                x + self.mu.value(t) * dt + self.sigma.value(t) * dw
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::mean_and_variance;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
 
     #[test]
     fn test_abm_simulation() {
@@ -79,4 +988,829 @@ mod tests {
         assert_eq!(paths.len(), 50);
         assert_eq!(paths[0].len(), 201); // n_steps + 1
     }
+
+    #[test]
+    fn test_abm_seed_is_reproducible() {
+        let abm = ArithmeticBrownianMotion::new(0.05, 0.4, 20, 100, 1.0, 200.0).with_seed(42);
+        assert_eq!(abm.simulate(), abm.simulate());
+
+        let other = ArithmeticBrownianMotion::new(0.05, 0.4, 20, 100, 1.0, 200.0).with_seed(43);
+        assert_ne!(abm.simulate(), other.simulate());
+    }
+
+    #[test]
+    fn test_abm_simulate_with_rng() {
+        let abm = ArithmeticBrownianMotion::new(0.05, 0.4, 20, 100, 1.0, 200.0);
+        let first = abm.simulate_with_rng(&mut ChaCha8Rng::seed_from_u64(7));
+        let second = abm.simulate_with_rng(&mut ChaCha8Rng::seed_from_u64(7));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_abm_paths_use_independent_streams() {
+        // Path `i` of the seeded `simulate` only depends on the seed and `i`.
+        let abm = ArithmeticBrownianMotion::new(0.05, 0.4, 20, 100, 1.0, 200.0).with_seed(7);
+        let paths = abm.simulate();
+        for i in [0, 7, 19] {
+            let path = abm.sample_path(&mut engine::path_rng(7, i));
+            assert_eq!(paths[i], path[..]);
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_abm_parallel_is_thread_count_independent() {
+        let abm = ArithmeticBrownianMotion::new(0.05, 0.4, 257, 50, 1.0, 200.0).with_seed(3);
+        let run = |threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| abm.simulate())
+        };
+        let single = run(1);
+        assert_eq!(single, run(4));
+        assert_eq!(single, run(7));
+    }
+
+    #[test]
+    fn test_abm_try_new_rejects_invalid_parameters() {
+        assert!(ArithmeticBrownianMotion::try_new(0.05, 0.4, 10, 100, 1.0, 200.0).is_ok());
+        assert_eq!(
+            ArithmeticBrownianMotion::try_new(0.05, -0.4, 10, 100, 1.0, 200.0).err(),
+            Some(AbmError::NegativeVolatility(-0.4))
+        );
+        assert_eq!(
+            ArithmeticBrownianMotion::try_new(0.05, 0.4, 10, 0, 1.0, 200.0).err(),
+            Some(AbmError::ZeroSteps)
+        );
+        assert_eq!(
+            ArithmeticBrownianMotion::try_new(0.05, 0.4, 10, 100, -1.0, 200.0).err(),
+            Some(AbmError::NonPositiveHorizon(-1.0))
+        );
+        assert!(matches!(
+            ArithmeticBrownianMotion::try_new(0.05, 0.4, 10, 100, f64::NAN, 200.0),
+            Err(AbmError::NonFinite { name: "t_end", .. })
+        ));
+        assert_eq!(
+            ArithmeticBrownianMotion::try_new(0.05, 0.4, 10, 100, 1.0, f64::INFINITY).err(),
+            Some(AbmError::NonFinite {
+                name: "s_0",
+                value: f64::INFINITY
+            })
+        );
+    }
+
+    #[test]
+    fn test_abm_try_simulate_reports_invalid_state() {
+        let mut abm = ArithmeticBrownianMotion::new(0.05, 0.4, 10, 100, 1.0, 200.0);
+        assert!(abm.try_simulate().is_ok());
+        abm.n_steps = 0;
+        assert_eq!(abm.try_simulate().err(), Some(AbmError::ZeroSteps));
+    }
+
+    #[test]
+    #[should_panic(expected = "volatility must be non-negative")]
+    fn test_abm_simulate_panics_on_invalid_state() {
+        ArithmeticBrownianMotion::new(0.05, -0.4, 10, 100, 1.0, 200.0).simulate();
+    }
+
+    #[test]
+    fn test_abm_builder() {
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(0.05)
+            .sigma(0.4)
+            .n_paths(50)
+            .n_steps(200)
+            .t_end(2.0)
+            .s_0(200.0)
+            .seed(9)
+            .scheme(Scheme::EulerMaruyama)
+            .build()
+            .unwrap();
+        assert_eq!(abm.mu, Coefficient::Constant(0.05));
+        assert_eq!(abm.sigma, Coefficient::Constant(0.4));
+        assert_eq!((abm.t_end, abm.s_0), (2.0, 200.0));
+        assert_eq!((abm.n_paths, abm.n_steps), (50, 200));
+        assert_eq!(abm.seed, Some(9));
+        assert_eq!(abm.scheme, Scheme::EulerMaruyama);
+
+        let positional = ArithmeticBrownianMotion::new(0.05, 0.4, 50, 200, 2.0, 200.0)
+            .with_seed(9)
+            .with_scheme(Scheme::EulerMaruyama);
+        assert_eq!(abm.simulate(), positional.simulate());
+    }
+
+    #[test]
+    fn test_abm_builder_defaults_and_validation() {
+        let abm = ArithmeticBrownianMotion::builder().build().unwrap();
+        assert_eq!(
+            (abm.mu.as_constant(), abm.sigma.as_constant()),
+            (Some(0.0), Some(1.0))
+        );
+        assert_eq!((abm.t_end, abm.s_0), (1.0, 0.0));
+        assert_eq!((abm.n_paths, abm.n_steps), (1, 100));
+        assert_eq!(abm.seed, None);
+        assert_eq!(abm.scheme, Scheme::Exact);
+
+        let err = ArithmeticBrownianMotion::builder()
+            .sigma(-1.0)
+            .build()
+            .err();
+        assert_eq!(err, Some(AbmError::NegativeVolatility(-1.0)));
+    }
+
+    #[test]
+    fn test_abm_schemes_agree_for_constant_coefficients() {
+        let exact = ArithmeticBrownianMotion::new(0.05, 0.4, 20, 100, 1.0, 200.0).with_seed(4);
+        let euler = ArithmeticBrownianMotion::new(0.05, 0.4, 20, 100, 1.0, 200.0)
+            .with_seed(4)
+            .with_scheme(Scheme::EulerMaruyama);
+        let (a, b) = (exact.simulate(), euler.simulate());
+        assert!(a
+            .as_slice()
+            .iter()
+            .zip(b.as_slice())
+            .all(|(x, y)| (x - y).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_abm_piecewise_volatility_is_exact() {
+        // sigma = 0.2 on [0, 0.5) and 0.6 afterwards: Var S(1) = 0.04 * 0.5 + 0.36 * 0.5.
+        // A single step straddling the break still has the right variance.
+        let sigma = Coefficient::piecewise(vec![0.5], vec![0.2, 0.6]).unwrap();
+        for n_steps in [1, 3, 50] {
+            let abm = ArithmeticBrownianMotion::builder()
+                .mu(0.1)
+                .sigma(sigma.clone())
+                .s_0(1.0)
+                .n_steps(n_steps)
+                .n_paths(20_000)
+                .seed(8)
+                .build()
+                .unwrap();
+            let (mean, var) = mean_and_variance(&abm.simulate().terminal_values());
+            let (expected_mean, expected_var) = (1.1, 0.2);
+            let n = abm.n_paths as f64;
+            assert!((mean - expected_mean).abs() < 5.0 * (expected_var / n).sqrt());
+            assert!(
+                (var - expected_var).abs() < 5.0 * expected_var * (2.0 / (n - 1.0)).sqrt(),
+                "{n_steps} steps: variance {var}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_abm_function_drift_exact_versus_euler() {
+        // With zero volatility the exact scheme integrates mu(t) = 2t exactly while
+        // Euler accumulates the left Riemann sum.
+        let build = |scheme| {
+            ArithmeticBrownianMotion::builder()
+                .mu(Coefficient::function(|t| 2.0 * t))
+                .sigma(0.0)
+                .n_steps(4)
+                .scheme(scheme)
+                .seed(1)
+                .build()
+                .unwrap()
+        };
+        let exact = build(Scheme::Exact).simulate();
+        let euler = build(Scheme::EulerMaruyama).simulate();
+        for (j, &t) in exact.times().iter().enumerate() {
+            assert!((exact[0][j] - t * t).abs() < 1e-12);
+        }
+        // sum_{k<4} 2 * (k / 4) * 0.25 = 0.75
+        assert!((euler[0][4] - 0.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_abm_validates_piecewise_volatility() {
+        let abm = ArithmeticBrownianMotion::builder()
+            .sigma(Coefficient::piecewise(vec![0.5], vec![0.2, -0.1]).unwrap())
+            .build();
+        assert_eq!(abm.err(), Some(AbmError::NegativeVolatility(-0.1)));
+
+        // The variant is public, so its layout is checked as well.
+        let malformed = Coefficient::PiecewiseConstant {
+            breaks: vec![0.5],
+            values: vec![1.0],
+        };
+        let abm = ArithmeticBrownianMotion::builder().sigma(malformed).build();
+        assert!(matches!(abm.err(), Some(AbmError::InvalidCoefficient(_))));
+    }
+
+    #[test]
+    fn test_abm_is_unwind_safe() {
+        fn assert_unwind_safe<T: std::panic::UnwindSafe + std::panic::RefUnwindSafe>() {}
+        assert_unwind_safe::<ArithmeticBrownianMotion>();
+    }
+
+    #[test]
+    fn test_abm_time_grid() {
+        let grid = vec![0.0, 0.1, 0.5, 0.55, 2.0];
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(0.5)
+            .sigma(0.8)
+            .s_0(1.0)
+            .n_paths(20_000)
+            .time_grid(grid.clone())
+            .seed(6)
+            .build()
+            .unwrap();
+        assert_eq!((abm.n_steps, abm.t_end), (4, 2.0));
+
+        let paths = abm.simulate();
+        assert_eq!(paths.times(), &grid[..]);
+        assert_eq!(paths[0].len(), 5);
+
+        // The long last step has variance sigma^2 * 1.45 and mean mu * 1.45.
+        let last: Vec<f64> = paths.iter().map(|p| p[4] - p[3]).collect();
+        let n = last.len() as f64;
+        let (mean, var) = mean_and_variance(&last);
+        let (expected_mean, expected_var) = (0.5 * 1.45, 0.64 * 1.45);
+        assert!((mean - expected_mean).abs() < 5.0 * (expected_var / n).sqrt());
+        assert!((var - expected_var).abs() < 5.0 * expected_var * (2.0 / (n - 1.0)).sqrt());
+
+        let (terminal_mean, _) = mean_and_variance(&paths.terminal_values());
+        assert!((terminal_mean - 2.0).abs() < 5.0 * (0.64 * 2.0 / n).sqrt());
+    }
+
+    #[test]
+    fn test_abm_time_grid_validation() {
+        let invalid = |times: Vec<f64>| {
+            ArithmeticBrownianMotion::builder()
+                .time_grid(times)
+                .build()
+                .err()
+        };
+        assert!(matches!(
+            invalid(vec![0.0]),
+            Some(AbmError::InvalidTimeGrid(_))
+        ));
+        assert!(matches!(
+            invalid(vec![0.1, 0.5]),
+            Some(AbmError::InvalidTimeGrid(_))
+        ));
+        assert!(matches!(
+            invalid(vec![0.0, 0.5, 0.5, 1.0]),
+            Some(AbmError::InvalidTimeGrid(_))
+        ));
+        assert!(matches!(
+            invalid(vec![0.0, 1.0, f64::NAN]),
+            Some(AbmError::InvalidTimeGrid(_))
+        ));
+
+        // The public fields must keep describing the grid.
+        let abm = ArithmeticBrownianMotion::new(0.0, 1.0, 1, 1, 1.0, 0.0)
+            .with_time_grid(vec![0.0, 0.5, 2.0]);
+        assert!(abm.validate().is_ok());
+        let mut stretched = abm.clone();
+        stretched.t_end = 3.0;
+        assert!(matches!(
+            stretched.validate(),
+            Err(AbmError::InvalidTimeGrid(_))
+        ));
+        let mut refined = abm;
+        refined.n_steps = 4;
+        assert!(matches!(
+            refined.validate(),
+            Err(AbmError::InvalidTimeGrid(_))
+        ));
+    }
+
+    #[test]
+    fn test_abm_antithetic_pairs() {
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(0.2)
+            .sigma(0.5)
+            .s_0(1.0)
+            .n_paths(10)
+            .n_steps(20)
+            .antithetic(true)
+            .seed(8)
+            .build()
+            .unwrap();
+        let paths = abm.simulate();
+        assert!(paths.is_antithetic());
+        for k in 0..5 {
+            let (path, mirror) = (&paths[2 * k], &paths[2 * k + 1]);
+            for (j, &t) in paths.times().iter().enumerate() {
+                // The pair is symmetric around the mean path s_0 + mu * t.
+                let centre = 1.0 + 0.2 * t;
+                assert!((path[j] + mirror[j] - 2.0 * centre).abs() < 1e-12);
+            }
+        }
+        // Pair k is driven by stream k, i.e. by the first path of a plain run
+        // with the same seed.
+        let plain = abm.clone().with_antithetic(false).simulate();
+        assert_eq!(paths[2], plain[1]);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        assert!(abm.simulate_with_rng(&mut rng).is_antithetic());
+        let odd = ArithmeticBrownianMotion::builder()
+            .n_paths(3)
+            .antithetic(true)
+            .build();
+        assert_eq!(odd.err(), Some(AbmError::OddAntitheticPaths(3)));
+    }
+
+    #[test]
+    fn test_abm_antithetic_estimates() {
+        let build = |antithetic| {
+            ArithmeticBrownianMotion::builder()
+                .mu(0.1)
+                .sigma(0.3)
+                .s_0(1.0)
+                .n_paths(4_000)
+                .n_steps(4)
+                .antithetic(antithetic)
+                .seed(12)
+                .build()
+                .unwrap()
+                .simulate()
+        };
+        let (plain, antithetic) = (build(false), build(true));
+        let terminal = |p: &[f64]| *p.last().unwrap();
+        let call = |p: &[f64]| (p.last().unwrap() - 1.0).max(0.0);
+
+        // Linear payoffs are reproduced exactly by every antithetic pair.
+        let estimate = antithetic.estimate(terminal);
+        assert_eq!(estimate.n_samples, 2_000);
+        assert!((estimate.mean - 1.1).abs() < 1e-12);
+        assert!(estimate.std_error < 1e-12);
+
+        // A monotone payoff has a smaller standard error per path pair.
+        let (plain_call, antithetic_call) = (plain.estimate(call), antithetic.estimate(call));
+        assert_eq!(plain_call.n_samples, 4_000);
+        assert!(antithetic_call.std_error < plain_call.std_error);
+        let diff = (plain_call.mean - antithetic_call.mean).abs();
+        assert!(diff < 5.0 * plain_call.std_error.hypot(antithetic_call.std_error));
+    }
+
+    #[test]
+    fn test_abm_qmc_paths() {
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(0.4)
+            .sigma(0.9)
+            .s_0(1.0)
+            .n_paths(1 << 12)
+            .n_steps(16)
+            .seed(2)
+            .build()
+            .unwrap();
+        let paths = abm.simulate_qmc(PathConstruction::BrownianBridge);
+        assert_eq!(paths, abm.simulate_qmc(PathConstruction::BrownianBridge));
+        assert_eq!(paths.times(), abm.simulate().times());
+
+        // Stratified points reproduce the first two moments far more
+        // accurately than the Monte Carlo standard error.
+        let (mean, var) = mean_and_variance(&paths.terminal_values());
+        let n = paths.len() as f64;
+        assert!((mean - 1.4).abs() < 0.2 * (0.81 / n).sqrt());
+        assert!((var - 0.81).abs() < 0.2 * 0.81 * (2.0 / n).sqrt());
+
+        let pca = abm.simulate_qmc(PathConstruction::Pca);
+        let (mean, _) = mean_and_variance(&pca.terminal_values());
+        assert!((mean - 1.4).abs() < 0.2 * (0.81 / n).sqrt());
+    }
+
+    #[test]
+    fn test_abm_qmc_long_grid() {
+        // Steps beyond the Joe-Kuo table use extended coordinates, which the
+        // bridge assigns to the least important directions.
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(0.4)
+            .sigma(0.9)
+            .s_0(1.0)
+            .n_paths(1 << 12)
+            .n_steps(252)
+            .seed(6)
+            .build()
+            .unwrap();
+        let paths = abm.simulate_qmc(PathConstruction::BrownianBridge);
+        assert_eq!(paths.n_times(), 253);
+        let (mean, var) = mean_and_variance(&paths.terminal_values());
+        let n = paths.len() as f64;
+        assert!((mean - 1.4).abs() < 0.2 * (0.81 / n).sqrt());
+        assert!((var - 0.81).abs() < 0.2 * 0.81 * (2.0 / n).sqrt());
+
+        // The midpoint is driven by the second coordinate.
+        let (mean, var) = mean_and_variance(&paths.column(126).collect::<Vec<_>>());
+        assert!((mean - 1.2).abs() < 0.2 * (0.405 / n).sqrt());
+        assert!((var - 0.405).abs() < 0.2 * 0.405 * (2.0 / n).sqrt());
+    }
+
+    #[test]
+    fn test_abm_rqmc_call_price() {
+        let (mu, sigma, s_0, strike) = (0.1, 0.5, 1.0, 1.2);
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(mu)
+            .sigma(sigma)
+            .s_0(s_0)
+            .n_paths(1 << 10)
+            .n_steps(32)
+            .seed(5)
+            .build()
+            .unwrap();
+        let call = |p: &[f64]| (p.last().unwrap() - strike).max(0.0);
+        let rqmc = abm.rqmc_estimate(PathConstruction::BrownianBridge, 16, call);
+        assert_eq!(rqmc.n_samples, 16);
+
+        // Bachelier price of the call on S(1) ~ N(s_0 + mu, sigma^2).
+        let d = (s_0 + mu - strike) / sigma;
+        let exact = (s_0 + mu - strike) * normal::cdf(d) + sigma * normal::pdf(d);
+        assert!((rqmc.mean - exact).abs() < 5.0 * rqmc.std_error);
+
+        // Plain Monte Carlo with the same total number of paths.
+        let mc = ArithmeticBrownianMotion {
+            n_paths: 16 << 10,
+            ..abm.clone()
+        }
+        .simulate()
+        .estimate(call);
+        assert!(rqmc.std_error < 0.2 * mc.std_error, "{rqmc:?} vs {mc:?}");
+    }
+
+    #[test]
+    fn test_abm_bridge_moments() {
+        let (sigma, s_0, terminal, t_end) = (0.6, 1.0, 3.0, 2.0);
+        let build = |mu: f64| {
+            ArithmeticBrownianMotion::builder()
+                .mu(mu)
+                .sigma(sigma)
+                .s_0(s_0)
+                .t_end(t_end)
+                .n_steps(8)
+                .n_paths(20_000)
+                .seed(10)
+                .build()
+                .unwrap()
+        };
+        let paths = build(0.3).simulate_bridge(terminal);
+        assert!(paths.iter().all(|p| p[0] == s_0 && p[8] == terminal));
+
+        // At t = 0.5: mean s_0 + t / T * (terminal - s_0), variance sigma^2 t (T - t) / T.
+        let values: Vec<f64> = paths.column(2).collect();
+        let n = values.len() as f64;
+        let (mean, var) = mean_and_variance(&values);
+        let (expected_mean, expected_var) = (1.5, 0.36 * 0.5 * 1.5 / 2.0);
+        assert!((mean - expected_mean).abs() < 5.0 * (expected_var / n).sqrt());
+        assert!((var - expected_var).abs() < 5.0 * expected_var * (2.0 / (n - 1.0)).sqrt());
+
+        // A constant drift does not change the bridge.
+        let other = build(-1.0).simulate_bridge(terminal);
+        for (a, b) in paths.as_slice().iter().zip(other.as_slice()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_abm_bridge_time_dependent_volatility() {
+        // All the variance is in [0, 1): the bridge is pinned on [1, 2].
+        let sigma = Coefficient::piecewise(vec![1.0], vec![0.5, 0.0]).unwrap();
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(Coefficient::function(|t| t))
+            .sigma(sigma)
+            .t_end(2.0)
+            .n_steps(4)
+            .n_paths(100)
+            .seed(3)
+            .build()
+            .unwrap();
+        let paths = abm.simulate_bridge(5.0);
+        for path in &paths {
+            // S(1.5) = S(2) - int_1.5^2 t dt, S(1) = S(2) - int_1^2 t dt.
+            assert!((path[3] - (5.0 - 0.875)).abs() < 1e-12);
+            assert!((path[2] - (5.0 - 1.5)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_abm_refine() {
+        let sigma = 0.8;
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(0.5)
+            .sigma(sigma)
+            .n_steps(4)
+            .n_paths(20_000)
+            .seed(4)
+            .build()
+            .unwrap();
+        let coarse = abm.simulate();
+        let fine_times = uniform_grid(16, 1.0);
+        let fine = abm.refine(&coarse, &fine_times).unwrap();
+        assert_eq!(fine.times(), &fine_times[..]);
+        for (f, c) in fine.iter().zip(&coarse) {
+            for k in 0..5 {
+                assert_eq!(f[4 * k], c[k]);
+            }
+        }
+
+        // Fine increments have the law of direct simulation: N(mu dt, sigma^2 dt).
+        let dt = 1.0 / 16.0;
+        let increments: Vec<f64> = fine.iter().map(|p| p[6] - p[5]).collect();
+        let n = increments.len() as f64;
+        let (mean, var) = mean_and_variance(&increments);
+        let expected_var = sigma * sigma * dt;
+        assert!((mean - 0.5 * dt).abs() < 5.0 * (expected_var / n).sqrt());
+        assert!((var - expected_var).abs() < 5.0 * expected_var * (2.0 / (n - 1.0)).sqrt());
+
+        assert!(matches!(
+            abm.refine(&coarse, &[0.0, 0.5, 1.0]),
+            Err(AbmError::InvalidTimeGrid(_))
+        ));
+    }
+
+    #[test]
+    fn test_abm_refine_own_output() {
+        // Refining a seeded model's own paths must not reuse its draws, which
+        // would correlate the fill with the coarse increments.
+        let abm = ArithmeticBrownianMotion::builder()
+            .n_steps(4)
+            .n_paths(20_000)
+            .seed(8)
+            .build()
+            .unwrap();
+        let fine = abm.refine(&abm.simulate(), &uniform_grid(16, 1.0)).unwrap();
+        let expected_var = 1.0 / 16.0;
+        for j in 0..16 {
+            let increments: Vec<f64> = fine.iter().map(|p| p[j + 1] - p[j]).collect();
+            let n = increments.len() as f64;
+            let (mean, var) = mean_and_variance(&increments);
+            assert!(
+                mean.abs() < 5.0 * (expected_var / n).sqrt(),
+                "step {j}: {mean}"
+            );
+            assert!(
+                (var - expected_var).abs() < 5.0 * expected_var * (2.0 / (n - 1.0)).sqrt(),
+                "step {j}: {var}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_abm_refine_rejects_invalid_input() {
+        let abm = ArithmeticBrownianMotion::new(0.0, 1.0, 2, 2, 1.0, 0.0).with_seed(1);
+        let coarse = abm.simulate();
+        assert!(matches!(
+            abm.refine(&coarse, &[0.0, 0.5, 1.0, 1.5, 2.0]),
+            Err(AbmError::InvalidTimeGrid(_))
+        ));
+
+        let mut negative = abm.clone();
+        negative.sigma = Coefficient::Constant(-1.0);
+        assert!(matches!(
+            negative.refine(&coarse, &uniform_grid(4, 1.0)),
+            Err(AbmError::NegativeVolatility(_))
+        ));
+    }
+
+    #[test]
+    fn test_abm_simulate_terminal() {
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(Coefficient::function(|t| 2.0 * t))
+            .sigma(Coefficient::piecewise(vec![0.5], vec![0.4, 0.8]).unwrap())
+            .s_0(1.0)
+            .n_paths(50_000)
+            .seed(14)
+            .build()
+            .unwrap();
+        let values = abm.simulate_terminal();
+        assert_eq!(values.len(), 50_000);
+        assert_eq!(values, abm.simulate_terminal());
+
+        // Mean 1 + 1, variance 0.16 * 0.5 + 0.64 * 0.5.
+        let n = values.len() as f64;
+        let (mean, var) = mean_and_variance(&values);
+        let expected_var: f64 = 0.4;
+        assert!((mean - 2.0).abs() < 5.0 * (expected_var / n).sqrt());
+        assert!((var - expected_var).abs() < 5.0 * expected_var * (2.0 / (n - 1.0)).sqrt());
+
+        let antithetic = abm.with_antithetic(true).simulate_terminal();
+        for pair in antithetic.chunks_exact(2) {
+            assert!((pair[0] + pair[1] - 4.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_abm_streaming_matches_simulate() {
+        for antithetic in [false, true] {
+            let abm = ArithmeticBrownianMotion::builder()
+                .mu(Coefficient::function(|t| 1.0 - t))
+                .sigma(0.4)
+                .n_paths(6)
+                .n_steps(5)
+                .antithetic(antithetic)
+                .seed(21)
+                .build()
+                .unwrap();
+            let paths = abm.simulate();
+
+            let streamed = abm.paths();
+            assert_eq!(streamed.len(), 6);
+            for (path, expected) in streamed.zip(&paths) {
+                assert_eq!(path, expected);
+            }
+
+            let steps: Vec<(f64, Vec<f64>)> = abm.steps().collect();
+            assert_eq!(steps.len(), 6);
+            for (j, (t, values)) in steps.iter().enumerate() {
+                assert_eq!(*t, paths.times()[j]);
+                assert_eq!(*values, paths.column(j).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "transition from time 0 to earlier time -1")]
+    fn test_abm_cdf_rejects_negative_time() {
+        let abm = ArithmeticBrownianMotion::new(0.0, 1.0, 1, 1, 1.0, 0.0);
+        let _ = abm.cdf(-1.0, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "time must be non-negative, got -1")]
+    fn test_abm_variance_rejects_negative_time() {
+        let abm = ArithmeticBrownianMotion::new(0.0, 1.0, 1, 1, 1.0, 0.0);
+        let _ = abm.variance(-1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "time must be non-negative, got -0.5")]
+    fn test_abm_covariance_rejects_negative_time() {
+        let abm = ArithmeticBrownianMotion::new(0.0, 1.0, 1, 1, 1.0, 0.0);
+        let _ = abm.covariance(-0.5, 1.0);
+    }
+
+    #[test]
+    fn test_abm_streaming_without_seed() {
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(0.5)
+            .n_paths(4)
+            .n_steps(3)
+            .s_0(1.0)
+            .antithetic(true)
+            .build()
+            .unwrap();
+
+        let mut paths = abm.paths();
+        for remaining in (1..=2).rev() {
+            assert_eq!(paths.len(), 2 * remaining);
+            let (path, mirror) = (paths.next().unwrap(), paths.next().unwrap());
+            assert_eq!(path.len(), 4);
+            assert_ne!(path, mirror);
+            for (j, t) in abm.time_points().into_iter().enumerate() {
+                assert!((path[j] + mirror[j] - 2.0 * abm.mean(t)).abs() < 1e-12);
+            }
+        }
+        assert_eq!(paths.len(), 0);
+        assert!(paths.next().is_none());
+
+        let mut steps = abm.steps();
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps.next(), Some((0.0, vec![1.0; 4])));
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps.map(|(_, values)| values.len()).sum::<usize>(), 12);
+    }
+
+    #[test]
+    fn test_abm_analytic_moments() {
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(Coefficient::function(|t| 1.0 + t))
+            .sigma(Coefficient::piecewise(vec![1.0], vec![0.5, 1.0]).unwrap())
+            .n_paths(20_000)
+            .n_steps(20)
+            .t_end(2.0)
+            .s_0(3.0)
+            .seed(25)
+            .build()
+            .unwrap();
+        // mean = 3 + t + t^2 / 2, variance = 0.25 + (t - 1) past the break.
+        assert!((abm.mean(2.0) - 7.0).abs() < 1e-12);
+        assert!((abm.variance(2.0) - 1.25).abs() < 1e-12);
+        assert!((abm.variance(0.5) - 0.125).abs() < 1e-12);
+        assert_eq!(abm.covariance(2.0, 0.5), abm.variance(0.5));
+
+        let paths = abm.simulate();
+        let n = paths.len() as f64;
+        let (mean, var) = mean_and_variance(&paths.terminal_values());
+        assert!((mean - abm.mean(2.0)).abs() < 5.0 * (abm.variance(2.0) / n).sqrt());
+        assert!((var - abm.variance(2.0)).abs() < 5.0 * abm.variance(2.0) * (2.0 / n).sqrt());
+
+        // Sample covariance of S(0.5) and S(2).
+        let (j, k) = (5, 20);
+        let (m_j, m_k) = (abm.mean(0.5), abm.mean(2.0));
+        let cov = paths
+            .iter()
+            .map(|p| (p[j] - m_j) * (p[k] - m_k))
+            .sum::<f64>()
+            / n;
+        let sd = (abm.variance(0.5) * abm.variance(2.0)).sqrt();
+        assert!((cov - abm.covariance(0.5, 2.0)).abs() < 5.0 * sd * (2.0 / n).sqrt());
+    }
+
+    #[test]
+    fn test_abm_distribution_functions() {
+        let abm = ArithmeticBrownianMotion::new(0.5, 2.0, 1, 1, 1.0, 1.0);
+        // S(1) ~ N(1.5, 4).
+        assert!((abm.cdf(1.0, 1.5) - 0.5).abs() < 1e-15);
+        assert!((abm.quantile(1.0, 0.975) - (1.5 + 2.0 * normal::Z_95)).abs() < 1e-8);
+        for p in [0.01, 0.3, 0.9] {
+            assert!((abm.cdf(0.7, abm.quantile(0.7, p)) - p).abs() < 1e-12);
+        }
+        let peak = 1.0 / (2.0 * (2.0 * std::f64::consts::PI).sqrt());
+        assert!((abm.density(1.0, 1.5) - peak).abs() < 1e-15);
+
+        // The transition density is the density of the increment, and
+        // Chapman-Kolmogorov holds by midpoint integration over S(0.4).
+        assert_eq!(
+            abm.transition_density(0.4, 2.0, 1.0, 2.5),
+            abm.transition_density(0.0, 1.0, 0.6, 1.5)
+        );
+        let h = 1e-3;
+        let integral: f64 = (-10_000..10_000)
+            .map(|i| {
+                let z = 1.2 + (i as f64 + 0.5) * h;
+                abm.density(0.4, z) * abm.transition_density(0.4, z, 1.0, 0.0) * h
+            })
+            .sum();
+        assert!((integral - abm.density(1.0, 0.0)).abs() < 1e-9);
+
+        assert_eq!(abm.cdf(0.0, 0.999), 0.0);
+        assert_eq!(abm.quantile(0.0, 0.3), 1.0);
+        assert_eq!(abm.density(0.0, 2.0), 0.0);
+    }
+
+    /// Only uses the `StochasticProcess` interface.
+    fn generic_terminal_mean<P: StochasticProcess>(process: &P) -> f64 {
+        let paths = process.simulate();
+        paths.iter().map(|p| *p.last().unwrap()).sum::<f64>() / paths.len() as f64
+    }
+
+    #[test]
+    fn test_abm_as_stochastic_process() {
+        let abm = ArithmeticBrownianMotion::new(0.5, 0.2, 1_000, 10, 2.0, 10.0).with_seed(1);
+        assert_eq!(StochasticProcess::drift(&abm, 0.3, 12.0), 0.5);
+        assert_eq!(StochasticProcess::diffusion(&abm, 0.3, 12.0), 0.2);
+        assert_eq!(abm.initial_value(), 10.0);
+        assert_eq!(abm.time_horizon(), 2.0);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        assert_eq!(StochasticProcess::sample_path(&abm, &mut rng).len(), 11);
+
+        let mean = generic_terminal_mean(&abm);
+        let std_err = 0.2 * 2.0_f64.sqrt() / 1_000_f64.sqrt();
+        assert!((mean - 11.0).abs() < 5.0 * std_err);
+    }
+
+    #[test]
+    fn test_abm_terminal_mean() {
+        let (mu, sigma, t_end, s_0) = (0.3, 0.8, 2.0, 100.0);
+        let n_paths = 20_000;
+        let abm = ArithmeticBrownianMotion::new(mu, sigma, n_paths, 50, t_end, s_0).with_seed(1);
+        let (mean, _) = mean_and_variance(&abm.simulate().terminal_values());
+
+        // S(T) ~ N(s_0 + mu * T, sigma^2 * T); allow five standard errors.
+        let std_err = sigma * t_end.sqrt() / (n_paths as f64).sqrt();
+        let expected = s_0 + mu * t_end;
+        assert!(
+            (mean - expected).abs() < 5.0 * std_err,
+            "terminal mean {mean} too far from {expected}"
+        );
+    }
+
+    #[test]
+    fn test_abm_terminal_variance() {
+        let (mu, sigma, t_end, s_0) = (0.3, 0.8, 2.0, 100.0);
+        let n_paths = 20_000;
+        let abm = ArithmeticBrownianMotion::new(mu, sigma, n_paths, 50, t_end, s_0).with_seed(2);
+        let (_, var) = mean_and_variance(&abm.simulate().terminal_values());
+
+        // For Gaussian samples the sample variance has standard error var * sqrt(2 / (n - 1)).
+        let expected = sigma * sigma * t_end;
+        let std_err = expected * (2.0 / (n_paths as f64 - 1.0)).sqrt();
+        assert!(
+            (var - expected).abs() < 5.0 * std_err,
+            "terminal variance {var} too far from {expected}"
+        );
+    }
+
+    #[test]
+    fn test_abm_increments_are_centred() {
+        // With zero drift the increments must be symmetric around zero; uniform
+        // draws would push every step upwards by sigma * 0.5 * sqrt(dt).
+        let (sigma, n_steps, n_paths) = (1.0, 100, 2_000);
+        let abm =
+            ArithmeticBrownianMotion::new(0.0, sigma, n_paths, n_steps, 1.0, 0.0).with_seed(3);
+        let paths = abm.simulate();
+        let n = (n_paths * n_steps) as f64;
+        let mean_increment = paths
+            .iter()
+            .flat_map(|p| p.windows(2).map(|w| w[1] - w[0]))
+            .sum::<f64>()
+            / n;
+        let dt = 1.0 / n_steps as f64;
+        let std_err = sigma * dt.sqrt() / n.sqrt();
+        assert!(mean_increment.abs() < 5.0 * std_err);
+    }
 }
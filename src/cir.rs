@@ -0,0 +1,578 @@
+use crate::{engine, AbmError, PathMatrix, StochasticProcess};
+use rand::Rng;
+use rand_distr::{Distribution, Gamma, Poisson, StandardNormal};
+
+/// Discretization scheme used by [`CoxIngersollRoss`].
+///
+/// Plain Euler-Maruyama is not offered because it takes the square root of
+/// negative values as soon as a step overshoots zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CirScheme {
+    /// Euler with the state floored at zero inside the drift and diffusion
+    /// (Lord, Koekkoek and van Dijk). The reported value is `max(X, 0)`.
+    FullTruncation,
+    /// Euler with the absolute value taken after each step.
+    Reflection,
+    /// Andersen's quadratic-exponential moment-matching scheme.
+    QuadraticExponential,
+    /// Exact sampling from the scaled non-central chi-square transition density.
+    Exact,
+}
+
+/// The Cox-Ingersoll-Ross (CIR) model simulates a non-negative mean-reverting
+/// quantity, such as a short rate or an instantaneous variance, using the
+/// following formula:
+///
+/// dX = theta * (mu - X) * dt + sigma * sqrt(X) * d_w
+///
+/// Where:
+/// - `theta` is the speed of mean reversion
+/// - `mu` is the long-run mean
+/// - `sigma` is the volatility of the square-root diffusion
+/// - `d_w` is a Wiener process increment (Brownian motion), distributed as N(0, dt)
+pub struct CoxIngersollRoss {
+    pub theta: f64,
+    pub mu: f64,
+    pub sigma: f64,
+    pub n_paths: usize,
+    pub n_steps: usize,
+    pub t_end: f64,
+    pub x_0: f64,
+    /// The discretization scheme, `CirScheme::Exact` by default.
+    pub scheme: CirScheme,
+    /// Optional seed of the per-path random streams, see
+    /// [reproducibility](crate#reproducibility).
+    pub seed: Option<u64>,
+}
+
+impl CoxIngersollRoss {
+    /// Creates a new instance of the Cox-Ingersoll-Ross model using the exact scheme.
+    ///
+    /// # Arguments
+    ///
+    /// * `theta` - The speed of mean reversion.
+    /// * `mu` - The long-run mean.
+    /// * `sigma` - The volatility of the square-root diffusion.
+    /// * `n_paths` - Number of simulated paths.
+    /// * `n_steps` - Number of steps in each path.
+    /// * `t_end` - Total time of simulation.
+    /// * `x_0` - Initial value of the process (at t=0).
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `CoxIngersollRoss`.
+    pub fn new(
+        theta: f64,
+        mu: f64,
+        sigma: f64,
+        n_paths: usize,
+        n_steps: usize,
+        t_end: f64,
+        x_0: f64,
+    ) -> Self {
+        Self {
+            theta,
+            mu,
+            sigma,
+            n_paths,
+            n_steps,
+            t_end,
+            x_0,
+            scheme: CirScheme::Exact,
+            seed: None,
+        }
+    }
+
+    /// Like `new`, but validates the parameters.
+    ///
+    /// # Errors
+    ///
+    /// See `validate`.
+    pub fn try_new(
+        theta: f64,
+        mu: f64,
+        sigma: f64,
+        n_paths: usize,
+        n_steps: usize,
+        t_end: f64,
+        x_0: f64,
+    ) -> Result<Self, AbmError> {
+        let model = Self::new(theta, mu, sigma, n_paths, n_steps, t_end, x_0);
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// Checks that the current parameters describe a well-defined simulation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AbmError` if a parameter is not finite, `sigma` is
+    /// negative, `theta`, `mu` or `x_0` is negative (the transition of the
+    /// exact scheme is then undefined), `n_steps` is zero, or `t_end` is not
+    /// positive.
+    pub fn validate(&self) -> Result<(), AbmError> {
+        for (name, value) in [
+            ("theta", self.theta),
+            ("mu", self.mu),
+            ("sigma", self.sigma),
+            ("t_end", self.t_end),
+            ("x_0", self.x_0),
+        ] {
+            if !value.is_finite() {
+                return Err(AbmError::NonFinite { name, value });
+            }
+        }
+        if self.sigma < 0.0 {
+            return Err(AbmError::NegativeVolatility(self.sigma));
+        }
+        for (name, value) in [("theta", self.theta), ("mu", self.mu), ("x_0", self.x_0)] {
+            if value < 0.0 {
+                return Err(AbmError::NegativeParameter { name, value });
+            }
+        }
+        if self.n_steps == 0 {
+            return Err(AbmError::ZeroSteps);
+        }
+        if self.t_end <= 0.0 {
+            return Err(AbmError::NonPositiveHorizon(self.t_end));
+        }
+        Ok(())
+    }
+
+    /// Panics with a descriptive message if the parameters are invalid.
+    fn assert_valid(&self) {
+        if let Err(err) = self.validate() {
+            panic!("invalid CoxIngersollRoss: {err}");
+        }
+    }
+
+    /// Selects the discretization scheme.
+    pub fn with_scheme(mut self, scheme: CirScheme) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
+    /// Fixes the seed used by `simulate`, so that repeated runs produce
+    /// bit-identical paths.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Whether the Feller condition `2 * theta * mu >= sigma^2` holds, in which
+    /// case the process never reaches zero.
+    pub fn satisfies_feller(&self) -> bool {
+        2.0 * self.theta * self.mu >= self.sigma * self.sigma
+    }
+
+    /// Simulates paths with the configured scheme.
+    ///
+    /// # Returns
+    ///
+    /// A `PathMatrix` whose rows are the simulated paths.
+    ///
+    /// Each path has `n_steps + 1` non-negative values, including the initial value `x_0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn simulate(&self) -> PathMatrix {
+        self.assert_valid();
+        let mut paths = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.x_0);
+        engine::fill_paths(&mut paths, engine::base_seed(self.seed), |path, rng| {
+            self.fill_path(path, rng)
+        });
+        paths
+    }
+
+    /// Like `simulate`, but reports invalid parameters as an error.
+    ///
+    /// # Errors
+    ///
+    /// See `validate`.
+    pub fn try_simulate(&self) -> Result<PathMatrix, AbmError> {
+        self.validate()?;
+        Ok(self.simulate())
+    }
+
+    /// Simulates paths using a caller-supplied random number generator.
+    ///
+    /// The `seed` field is ignored; the paths are fully determined by the state of `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn simulate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> PathMatrix {
+        self.assert_valid();
+        let mut paths = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.x_0);
+        for path in paths.iter_mut() {
+            self.fill_path(path, rng);
+        }
+        paths
+    }
+
+    /// Simulates a single path of `n_steps + 1` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        self.assert_valid();
+        let mut path = vec![self.x_0; self.n_steps + 1];
+        self.fill_path(&mut path, rng);
+        path
+    }
+
+    /// Writes one path into `path`, whose length sets the number of steps.
+    fn fill_path<R: Rng + ?Sized>(&self, path: &mut [f64], rng: &mut R) {
+        let dt = self.t_end / self.n_steps as f64;
+        path[0] = self.x_0;
+        // Full truncation lets the underlying Euler state go negative; only its
+        // positive part is reported.
+        let mut state = self.x_0;
+
+        for value in path.iter_mut().skip(1) {
+            state = match self.scheme {
+                CirScheme::FullTruncation => {
+                    let x = state.max(0.0);
+                    let z: f64 = rng.sample(StandardNormal);
+                    state + self.theta * (self.mu - x) * dt + self.sigma * (x * dt).sqrt() * z
+                }
+                CirScheme::Reflection => {
+                    let z: f64 = rng.sample(StandardNormal);
+                    (state
+                        + self.theta * (self.mu - state) * dt
+                        + self.sigma * (state * dt).sqrt() * z)
+                        .abs()
+                }
+                // Without noise both transitions degenerate to a point mass.
+                CirScheme::QuadraticExponential | CirScheme::Exact if self.sigma == 0.0 => {
+                    self.mu + (state - self.mu) * (-self.theta * dt).exp()
+                }
+                CirScheme::QuadraticExponential => {
+                    QeBranch::new(state, self.theta, self.mu, self.sigma, dt).sample(rng)
+                }
+                CirScheme::Exact => self.exact_step(state, dt, rng),
+            };
+            *value = state.max(0.0);
+        }
+    }
+
+    /// Draws X(t + dt) given X(t) = `x` as `c * chi'^2(d, lambda)`, a scaled
+    /// non-central chi-square with `d = 4 * theta * mu / sigma^2` degrees of
+    /// freedom, sampled as a Poisson mixture of central chi-squares.
+    ///
+    /// The scale `c = sigma^2 * (1 - exp(-theta * dt)) / (4 * theta)` tends to
+    /// `sigma^2 * dt / 4` as `theta` goes to zero. Requires `sigma > 0`.
+    fn exact_step<R: Rng + ?Sized>(&self, x: f64, dt: f64, rng: &mut R) -> f64 {
+        let sigma2 = self.sigma * self.sigma;
+        let decay = (-self.theta * dt).exp();
+        let c = sigma2 * decay_integral(self.theta, dt) / 4.0;
+        let d = 4.0 * self.theta * self.mu / sigma2;
+        let lambda = x * decay / c;
+
+        let n = if lambda > 0.0 {
+            Poisson::new(0.5 * lambda).unwrap().sample(rng)
+        } else {
+            0.0
+        };
+        // chi^2(k) is Gamma(k / 2, 2).
+        let shape = 0.5 * d + n;
+        if shape <= 0.0 {
+            return 0.0;
+        }
+        c * Gamma::new(shape, 2.0).unwrap().sample(rng)
+    }
+
+    /// The analytic mean of X(t): `mu + (x_0 - mu) * exp(-theta * t)`.
+    pub fn mean(&self, t: f64) -> f64 {
+        self.mu + (self.x_0 - self.mu) * (-self.theta * t).exp()
+    }
+
+    /// The analytic variance of X(t):
+    ///
+    /// `x_0 * sigma^2 / theta * (e^{-theta t} - e^{-2 theta t}) + mu * sigma^2 / (2 theta) * (1 - e^{-theta t})^2`
+    pub fn variance(&self, t: f64) -> f64 {
+        cir_variance(self.x_0, self.theta, self.mu, self.sigma, t)
+    }
+
+    /// The mean of the stationary (Gamma) distribution, `mu`.
+    pub fn stationary_mean(&self) -> f64 {
+        self.mu
+    }
+
+    /// The variance of the stationary distribution, `mu * sigma^2 / (2 * theta)`.
+    pub fn stationary_variance(&self) -> f64 {
+        self.mu * self.sigma * self.sigma / (2.0 * self.theta)
+    }
+}
+
+/// Variance of a CIR process after time `t` started from `x`, written with
+/// `b = (1 - exp(-theta * t)) / theta` so that it stays finite as `theta`
+/// goes to zero, where it tends to `x * sigma^2 * t`.
+fn cir_variance(x: f64, theta: f64, mu: f64, sigma: f64, t: f64) -> f64 {
+    let decay = (-theta * t).exp();
+    let b = decay_integral(theta, t);
+    let sigma2 = sigma * sigma;
+    x * sigma2 * decay * b + mu * sigma2 * theta * b * b / 2.0
+}
+
+/// `int_0^t exp(-theta * s) ds = (1 - exp(-theta * t)) / theta`, with the
+/// limit `t` as `theta` goes to zero.
+fn decay_integral(theta: f64, t: f64) -> f64 {
+    let k = theta * t;
+    if k.abs() < 1e-12 {
+        t
+    } else {
+        -(-k).exp_m1() / theta
+    }
+}
+
+/// Threshold on `psi = s^2 / m^2` switching between the quadratic and exponential branches.
+const QE_PSI_CRITICAL: f64 = 1.5;
+
+/// The moment-matched distribution of one quadratic-exponential step.
+///
+/// The draw matches the exact conditional mean `m` and variance `s^2`: for
+/// small `psi = s^2 / m^2` as `a * (b + Z)^2`, otherwise from a mixture of a
+/// point mass at zero and an exponential tail.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum QeBranch {
+    /// X' = a * (b + Z)^2, stored with `b2 = b^2`.
+    Quadratic { a: f64, b2: f64 },
+    /// X' = 0 with probability `p`, otherwise exponential with rate `beta`.
+    Exponential { p: f64, beta: f64 },
+}
+
+impl QeBranch {
+    /// Fits the branch to the CIR transition from `x` over `dt`.
+    pub(crate) fn new(x: f64, theta: f64, mu: f64, sigma: f64, dt: f64) -> Self {
+        let m = mu + (x - mu) * (-theta * dt).exp();
+        let s2 = cir_variance(x, theta, mu, sigma, dt);
+        let psi = s2 / (m * m);
+
+        if psi <= QE_PSI_CRITICAL {
+            let inv = 2.0 / psi;
+            let b2 = inv - 1.0 + (inv * (inv - 1.0)).sqrt();
+            QeBranch::Quadratic {
+                a: m / (1.0 + b2),
+                b2,
+            }
+        } else {
+            let p = (psi - 1.0) / (psi + 1.0);
+            QeBranch::Exponential {
+                p,
+                beta: (1.0 - p) / m,
+            }
+        }
+    }
+
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match *self {
+            QeBranch::Quadratic { a, b2 } => {
+                let z: f64 = rng.sample(StandardNormal);
+                a * (b2.sqrt() + z).powi(2)
+            }
+            QeBranch::Exponential { p, beta } => {
+                let u: f64 = rng.gen();
+                if u <= p {
+                    0.0
+                } else {
+                    ((1.0 - p) / (1.0 - u)).ln() / beta
+                }
+            }
+        }
+    }
+}
+
+impl StochasticProcess for CoxIngersollRoss {
+    fn drift(&self, _t: f64, x: f64) -> f64 {
+        self.theta * (self.mu - x)
+    }
+
+    fn diffusion(&self, _t: f64, x: f64) -> f64 {
+        self.sigma * x.max(0.0).sqrt()
+    }
+
+    fn initial_value(&self) -> f64 {
+        self.x_0
+    }
+
+    fn time_horizon(&self) -> f64 {
+        self.t_end
+    }
+
+    fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        CoxIngersollRoss::sample_path(self, rng)
+    }
+
+    fn simulate(&self) -> PathMatrix {
+        CoxIngersollRoss::simulate(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mean_and_variance;
+
+    const SCHEMES: [CirScheme; 4] = [
+        CirScheme::FullTruncation,
+        CirScheme::Reflection,
+        CirScheme::QuadraticExponential,
+        CirScheme::Exact,
+    ];
+
+    fn terminal_values(cir: &CoxIngersollRoss) -> Vec<f64> {
+        cir.simulate().terminal_values()
+    }
+
+    #[test]
+    fn test_cir_simulation() {
+        for scheme in SCHEMES {
+            let cir = CoxIngersollRoss::new(1.0, 0.04, 0.2, 50, 200, 1.0, 0.04)
+                .with_scheme(scheme)
+                .with_seed(1);
+            let paths = cir.simulate();
+            assert_eq!(paths.len(), 50);
+            assert_eq!(paths[0].len(), 201);
+        }
+    }
+
+    #[test]
+    fn test_cir_positivity_without_feller() {
+        // 2 * theta * mu = 0.02 < sigma^2 = 0.25: the process hits zero frequently.
+        for scheme in SCHEMES {
+            let cir = CoxIngersollRoss::new(0.5, 0.02, 0.5, 200, 100, 2.0, 0.02)
+                .with_scheme(scheme)
+                .with_seed(9);
+            assert!(!cir.satisfies_feller());
+            assert!(cir
+                .simulate()
+                .iter()
+                .flatten()
+                .all(|&x| x >= 0.0 && x.is_finite()));
+        }
+    }
+
+    #[test]
+    fn test_cir_exact_and_qe_single_step_moments() {
+        // Both schemes reproduce the conditional moments for an arbitrarily large step.
+        for scheme in [CirScheme::Exact, CirScheme::QuadraticExponential] {
+            let cir = CoxIngersollRoss::new(1.2, 0.05, 0.3, 40_000, 1, 1.0, 0.1)
+                .with_scheme(scheme)
+                .with_seed(21);
+            let (mean, var) = mean_and_variance(&terminal_values(&cir));
+            let n = cir.n_paths as f64;
+            assert!(
+                (mean - cir.mean(1.0)).abs() < 5.0 * (cir.variance(1.0) / n).sqrt(),
+                "{scheme:?} mean {mean} vs {}",
+                cir.mean(1.0)
+            );
+            assert!(
+                (var / cir.variance(1.0) - 1.0).abs() < 0.05,
+                "{scheme:?} variance {var} vs {}",
+                cir.variance(1.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_cir_euler_schemes_converge_in_mean() {
+        for scheme in [CirScheme::FullTruncation, CirScheme::Reflection] {
+            let cir = CoxIngersollRoss::new(2.0, 0.04, 0.15, 10_000, 200, 1.0, 0.09)
+                .with_scheme(scheme)
+                .with_seed(4);
+            let (mean, _) = mean_and_variance(&terminal_values(&cir));
+            let std_err = (cir.variance(1.0) / cir.n_paths as f64).sqrt();
+            assert!(
+                (mean - cir.mean(1.0)).abs() < 5.0 * std_err + 1e-3,
+                "{scheme:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cir_schemes_share_seed() {
+        let build = |scheme| {
+            CoxIngersollRoss::new(2.0, 0.04, 0.1, 2_000, 100, 1.0, 0.04)
+                .with_scheme(scheme)
+                .with_seed(5)
+        };
+        let runs: Vec<PathMatrix> = SCHEMES.map(|scheme| build(scheme).simulate()).into();
+        for (scheme, paths) in SCHEMES.iter().zip(&runs) {
+            assert_eq!(*paths, build(*scheme).simulate(), "{scheme:?}");
+        }
+
+        // Far from zero the two Euler schemes consume the same draws in the
+        // same way, so on a shared seed they produce identical paths.
+        assert!(runs[0].iter().flatten().all(|&x| x > 0.0));
+        assert_eq!(runs[0], runs[1]);
+
+        // All schemes agree on the terminal distribution.
+        let cir = build(CirScheme::Exact);
+        let std_err = (cir.variance(1.0) / cir.n_paths as f64).sqrt();
+        for (scheme, paths) in SCHEMES.iter().zip(&runs) {
+            let (mean, _) = mean_and_variance(&paths.terminal_values());
+            assert!(
+                (mean - cir.mean(1.0)).abs() < 5.0 * std_err + 1e-4,
+                "{scheme:?} mean {mean} vs {}",
+                cir.mean(1.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_cir_zero_volatility_is_deterministic() {
+        // Without noise the exact and QE transitions follow the mean exactly;
+        // the Euler schemes follow their own deterministic recursion.
+        for scheme in SCHEMES {
+            let cir = CoxIngersollRoss::new(1.0, 0.04, 0.0, 2, 4, 1.0, 0.1)
+                .with_scheme(scheme)
+                .with_seed(6);
+            let paths = cir.simulate();
+            assert_eq!(paths[0], paths[1], "{scheme:?}");
+            if matches!(scheme, CirScheme::Exact | CirScheme::QuadraticExponential) {
+                for (j, &t) in paths.times().iter().enumerate() {
+                    assert!((paths[0][j] - cir.mean(t)).abs() < 1e-15, "{scheme:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cir_zero_theta() {
+        // Without mean reversion the mean stays at x_0 and the variance is
+        // x_0 * sigma^2 * t.
+        let cir = CoxIngersollRoss::new(0.0, 0.04, 0.2, 20_000, 4, 1.0, 0.04).with_seed(7);
+        assert_eq!(cir.mean(1.0), 0.04);
+        assert!((cir.variance(1.0) - 0.04 * 0.04).abs() < 1e-15);
+        let (mean, _) = mean_and_variance(&cir.simulate().terminal_values());
+        let std_err = (cir.variance(1.0) / cir.n_paths as f64).sqrt();
+        assert!((mean - 0.04).abs() < 5.0 * std_err, "{mean}");
+    }
+
+    #[test]
+    fn test_cir_validate() {
+        assert!(CoxIngersollRoss::try_new(1.0, 0.04, 0.2, 1, 1, 1.0, 0.04).is_ok());
+        assert_eq!(
+            CoxIngersollRoss::try_new(1.0, 0.04, -0.2, 1, 1, 1.0, 0.04).err(),
+            Some(AbmError::NegativeVolatility(-0.2))
+        );
+        assert_eq!(
+            CoxIngersollRoss::try_new(-1.0, 0.04, 0.2, 1, 1, 1.0, 0.04).err(),
+            Some(AbmError::NegativeParameter {
+                name: "theta",
+                value: -1.0
+            })
+        );
+        assert!(matches!(
+            CoxIngersollRoss::try_new(1.0, f64::NAN, 0.2, 1, 1, 1.0, 0.04),
+            Err(AbmError::NonFinite { name: "mu", .. })
+        ));
+        assert_eq!(
+            CoxIngersollRoss::try_new(1.0, 0.04, 0.2, 1, 0, 1.0, 0.04).err(),
+            Some(AbmError::ZeroSteps)
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use crate::AbmError;
+use std::fmt;
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+
+/// Absolute tolerance of the adaptive quadrature used for closure-based coefficients.
+const QUADRATURE_TOLERANCE: f64 = 1e-13;
+
+/// Maximum recursion depth of the adaptive quadrature.
+const QUADRATURE_MAX_DEPTH: u32 = 40;
+
+/// A deterministic, time-dependent model coefficient such as a drift `mu(t)`
+/// or a volatility `sigma(t)`.
+///
+/// Simulations only need the integrals of the coefficient (or of its square)
+/// over each step, which are exact for constant and piecewise-constant
+/// coefficients and computed by adaptive quadrature for closures.
+#[derive(Clone)]
+pub enum Coefficient {
+    /// The same value at all times.
+    Constant(f64),
+    /// `values[k]` on `[breaks[k - 1], breaks[k])`, with the first value
+    /// extending to minus infinity and the last one to plus infinity.
+    /// Build with [`Coefficient::piecewise`] to validate the layout.
+    PiecewiseConstant { breaks: Vec<f64>, values: Vec<f64> },
+    /// An arbitrary function of time. The `RefUnwindSafe` bound keeps the
+    /// processes holding coefficients unwind-safe.
+    Function(Arc<dyn Fn(f64) -> f64 + Send + Sync + RefUnwindSafe>),
+}
+
+impl Coefficient {
+    /// A piecewise-constant coefficient taking `values[k]` between `breaks[k - 1]`
+    /// and `breaks[k]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AbmError::InvalidCoefficient` unless there is exactly one more
+    /// value than breaks, the breaks are finite and strictly increasing, and the
+    /// values are finite.
+    pub fn piecewise(breaks: Vec<f64>, values: Vec<f64>) -> Result<Self, AbmError> {
+        check_layout(&breaks, &values)?;
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err(AbmError::InvalidCoefficient(
+                "values must be finite".to_string(),
+            ));
+        }
+        Ok(Coefficient::PiecewiseConstant { breaks, values })
+    }
+
+    /// A coefficient given by a closure of time.
+    pub fn function<F>(f: F) -> Self
+    where
+        F: Fn(f64) -> f64 + Send + Sync + RefUnwindSafe + 'static,
+    {
+        Coefficient::Function(Arc::new(f))
+    }
+
+    /// The value if the coefficient is constant.
+    pub fn as_constant(&self) -> Option<f64> {
+        match self {
+            Coefficient::Constant(c) => Some(*c),
+            _ => None,
+        }
+    }
+
+    /// The value at time `t`.
+    pub fn value(&self, t: f64) -> f64 {
+        match self {
+            Coefficient::Constant(c) => *c,
+            Coefficient::PiecewiseConstant { breaks, values } => {
+                values[breaks.partition_point(|&b| b <= t)]
+            }
+            Coefficient::Function(f) => f(t),
+        }
+    }
+
+    /// `int_a^b c(t) dt`.
+    pub fn integral(&self, a: f64, b: f64) -> f64 {
+        match self {
+            Coefficient::Constant(c) => c * (b - a),
+            Coefficient::PiecewiseConstant { breaks, values } => {
+                piecewise_integral(breaks, values, a, b, |v| v)
+            }
+            Coefficient::Function(f) => adaptive_simpson(|t| f(t), a, b),
+        }
+    }
+
+    /// `int_a^b c(t)^2 dt`, the integrated variance when `c` is a volatility.
+    pub fn integral_of_square(&self, a: f64, b: f64) -> f64 {
+        match self {
+            Coefficient::Constant(c) => c * c * (b - a),
+            Coefficient::PiecewiseConstant { breaks, values } => {
+                piecewise_integral(breaks, values, a, b, |v| v * v)
+            }
+            Coefficient::Function(f) => adaptive_simpson(|t| f(t).powi(2), a, b),
+        }
+    }
+
+    /// Checks the layout of a piecewise-constant coefficient, which may have
+    /// been built directly from the variant rather than with `piecewise`.
+    /// Finiteness of the values is left to the caller.
+    pub(crate) fn validate_layout(&self) -> Result<(), AbmError> {
+        match self {
+            Coefficient::PiecewiseConstant { breaks, values } => check_layout(breaks, values),
+            Coefficient::Constant(_) | Coefficient::Function(_) => Ok(()),
+        }
+    }
+
+    /// The values a piecewise-constant or constant coefficient can take;
+    /// empty for closures, which cannot be inspected.
+    pub(crate) fn known_values(&self) -> &[f64] {
+        match self {
+            Coefficient::Constant(c) => std::slice::from_ref(c),
+            Coefficient::PiecewiseConstant { values, .. } => values,
+            Coefficient::Function(_) => &[],
+        }
+    }
+}
+
+/// Checks that there is one more value than breaks and that the breaks are
+/// finite and strictly increasing.
+fn check_layout(breaks: &[f64], values: &[f64]) -> Result<(), AbmError> {
+    if values.len() != breaks.len() + 1 {
+        return Err(AbmError::InvalidCoefficient(format!(
+            "{} breaks need {} values, got {}",
+            breaks.len(),
+            breaks.len() + 1,
+            values.len()
+        )));
+    }
+    if breaks.iter().any(|b| !b.is_finite()) || breaks.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(AbmError::InvalidCoefficient(
+            "breaks must be finite and strictly increasing".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// `int_a^b g(c(t)) dt` for a piecewise-constant `c`, summed piece by piece.
+fn piecewise_integral(
+    breaks: &[f64],
+    values: &[f64],
+    a: f64,
+    b: f64,
+    g: impl Fn(f64) -> f64,
+) -> f64 {
+    if b < a {
+        return -piecewise_integral(breaks, values, b, a, g);
+    }
+    let mut total = 0.0;
+    let mut start = a;
+    let mut k = breaks.partition_point(|&x| x <= a);
+    while start < b {
+        let end = breaks.get(k).map_or(b, |&x| x.min(b));
+        total += g(values[k]) * (end - start);
+        start = end;
+        k += 1;
+    }
+    total
+}
+
+/// Adaptive Simpson quadrature of `f` over `[a, b]`.
+fn adaptive_simpson(f: impl Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+    if a == b {
+        return 0.0;
+    }
+    let (fa, fm, fb) = (f(a), f(0.5 * (a + b)), f(b));
+    let whole = (b - a) / 6.0 * (fa + 4.0 * fm + fb);
+    simpson_step(
+        &f,
+        a,
+        b,
+        fa,
+        fm,
+        fb,
+        whole,
+        QUADRATURE_TOLERANCE,
+        QUADRATURE_MAX_DEPTH,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn simpson_step(
+    f: &impl Fn(f64) -> f64,
+    a: f64,
+    b: f64,
+    fa: f64,
+    fm: f64,
+    fb: f64,
+    whole: f64,
+    tol: f64,
+    depth: u32,
+) -> f64 {
+    let m = 0.5 * (a + b);
+    let (lm, rm) = (0.5 * (a + m), 0.5 * (m + b));
+    let (flm, frm) = (f(lm), f(rm));
+    let left = (m - a) / 6.0 * (fa + 4.0 * flm + fm);
+    let right = (b - m) / 6.0 * (fm + 4.0 * frm + fb);
+    let delta = left + right - whole;
+    if depth == 0 || delta.abs() <= 15.0 * tol {
+        // Richardson extrapolation of the two Simpson estimates.
+        return left + right + delta / 15.0;
+    }
+    simpson_step(f, a, m, fa, flm, fm, left, 0.5 * tol, depth - 1)
+        + simpson_step(f, m, b, fm, frm, fb, right, 0.5 * tol, depth - 1)
+}
+
+impl From<f64> for Coefficient {
+    fn from(c: f64) -> Self {
+        Coefficient::Constant(c)
+    }
+}
+
+impl fmt::Debug for Coefficient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Coefficient::Constant(c) => f.debug_tuple("Constant").field(c).finish(),
+            Coefficient::PiecewiseConstant { breaks, values } => f
+                .debug_struct("PiecewiseConstant")
+                .field("breaks", breaks)
+                .field("values", values)
+                .finish(),
+            Coefficient::Function(_) => f.write_str("Function(..)"),
+        }
+    }
+}
+
+/// Closures compare equal only if they are the same shared function.
+impl PartialEq for Coefficient {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Coefficient::Constant(a), Coefficient::Constant(b)) => a == b,
+            (
+                Coefficient::PiecewiseConstant { breaks, values },
+                Coefficient::PiecewiseConstant {
+                    breaks: other_breaks,
+                    values: other_values,
+                },
+            ) => breaks == other_breaks && values == other_values,
+            (Coefficient::Function(a), Coefficient::Function(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_piecewise_values_and_integrals() {
+        let c = Coefficient::piecewise(vec![1.0, 2.0], vec![0.5, 2.0, -1.0]).unwrap();
+        assert_eq!(c.value(0.0), 0.5);
+        assert_eq!(c.value(1.0), 2.0);
+        assert_eq!(c.value(1.5), 2.0);
+        assert_eq!(c.value(5.0), -1.0);
+
+        // 0.5 * 0.5 + 2.0 * 1.0 - 1.0 * 0.5
+        assert!((c.integral(0.5, 2.5) - 1.75).abs() < 1e-15);
+        assert!((c.integral(2.5, 0.5) + 1.75).abs() < 1e-15);
+        assert!((c.integral(1.2, 1.7) - 1.0).abs() < 1e-15);
+        // 0.25 * 0.5 + 4.0 * 1.0 + 1.0 * 0.5
+        assert!((c.integral_of_square(0.5, 2.5) - 4.625).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_piecewise_validation() {
+        assert!(matches!(
+            Coefficient::piecewise(vec![1.0], vec![1.0]),
+            Err(AbmError::InvalidCoefficient(_))
+        ));
+        assert!(Coefficient::piecewise(vec![2.0, 1.0], vec![1.0, 2.0, 3.0]).is_err());
+        assert!(Coefficient::piecewise(vec![1.0], vec![1.0, f64::NAN]).is_err());
+
+        let direct = Coefficient::PiecewiseConstant {
+            breaks: vec![0.5],
+            values: vec![1.0],
+        };
+        assert!(matches!(
+            direct.validate_layout(),
+            Err(AbmError::InvalidCoefficient(_))
+        ));
+        assert!(Coefficient::Constant(1.0).validate_layout().is_ok());
+    }
+
+    #[test]
+    fn test_function_integrals() {
+        let c = Coefficient::function(|t| (2.0 * t).sin() + 1.0);
+        let exact = |a: f64, b: f64| b - a - 0.5 * ((2.0 * b).cos() - (2.0 * a).cos());
+        assert!((c.integral(0.1, 1.3) - exact(0.1, 1.3)).abs() < 1e-12);
+
+        let sq = Coefficient::function(|t| t.exp());
+        let exact_sq = 0.5 * ((2.0_f64).exp() - 1.0);
+        assert!((sq.integral_of_square(0.0, 1.0) - exact_sq).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_constant_conversion() {
+        let c: Coefficient = 0.3.into();
+        assert_eq!(c.as_constant(), Some(0.3));
+        assert!((c.integral(1.0, 3.0) - 0.6).abs() < 1e-15);
+        assert_eq!(c, Coefficient::Constant(0.3));
+        assert_eq!(Coefficient::function(|t| t).as_constant(), None);
+    }
+}
@@ -0,0 +1,313 @@
+//! Empirical strong and weak convergence orders of a discretization.
+//!
+//! A [`ConvergenceStudy`] simulates each scenario at successively halved step
+//! sizes, all driven by the same Brownian path, and compares the terminal
+//! values with an exact reference solution evaluated on that path. The error
+//! orders are the slopes of `log(error)` against `log(dt)`, with confidence
+//! intervals from independent batches of scenarios.
+
+use crate::normal::Z_95;
+use crate::{engine, StochasticProcess};
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+/// A process advanced one step at a time from given Brownian increments, so
+/// that simulations on different grids can share the same Brownian path.
+pub trait Discretization: StochasticProcess {
+    /// Advances the state `x` at time `t` over `dt`, given the Brownian
+    /// increment `dw ~ N(0, dt)` over the step.
+    fn step(&self, t: f64, x: f64, dt: f64, dw: f64) -> f64;
+}
+
+/// Errors of the discretization on one grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelError {
+    /// Number of steps up to the time horizon.
+    pub n_steps: usize,
+    /// The step size `t_end / n_steps`.
+    pub dt: f64,
+    /// The strong error `E|X_h(T) - X(T)|`.
+    pub strong_error: f64,
+    /// The weak error `|E[X_h(T)] - E[X(T)]|`.
+    pub weak_error: f64,
+}
+
+/// A convergence order with a 95% confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderEstimate {
+    /// The order fitted on all scenarios.
+    pub order: f64,
+    /// Lower end of the confidence interval.
+    pub lower: f64,
+    /// Upper end of the confidence interval.
+    pub upper: f64,
+}
+
+impl OrderEstimate {
+    /// Whether `order` lies in the confidence interval.
+    pub fn contains(&self, order: f64) -> bool {
+        self.lower <= order && order <= self.upper
+    }
+}
+
+/// The outcome of a [`ConvergenceStudy`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvergenceReport {
+    /// Errors per grid, from the coarsest to the finest.
+    pub levels: Vec<LevelError>,
+    /// The strong order of convergence.
+    pub strong_order: OrderEstimate,
+    /// The weak order of convergence.
+    pub weak_order: OrderEstimate,
+}
+
+/// Configuration of a convergence study.
+///
+/// # Example
+///
+/// ```
+/// use stochastic_abm::convergence::ConvergenceStudy;
+/// use stochastic_abm::{ArithmeticBrownianMotion, Coefficient, Scheme};
+///
+/// // Euler-Maruyama freezes the drift over each step, an O(dt) error.
+/// let mu = Coefficient::function(|t: f64| t.cos());
+/// let abm = ArithmeticBrownianMotion::builder()
+///     .mu(mu.clone())
+///     .sigma(0.3)
+///     .t_end(2.0)
+///     .scheme(Scheme::EulerMaruyama)
+///     .build()
+///     .unwrap();
+///
+/// let exact = |dw: &[f64]| mu.integral(0.0, 2.0) + 0.3 * dw.iter().sum::<f64>();
+/// let report = ConvergenceStudy::new(4, 5, 2_000).with_seed(1).run(&abm, exact);
+/// assert!((report.strong_order.order - 1.0).abs() < 0.05);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvergenceStudy {
+    /// Number of steps on the coarsest grid.
+    pub base_steps: usize,
+    /// Number of grids; grid `l` has `base_steps * 2^l` steps.
+    pub levels: usize,
+    /// Number of simulated scenarios.
+    pub n_paths: usize,
+    /// Number of batches used for the confidence intervals.
+    pub n_batches: usize,
+    /// Optional seed for reproducible studies.
+    pub seed: Option<u64>,
+}
+
+impl ConvergenceStudy {
+    /// Creates a study with `levels` grids starting from `base_steps` steps,
+    /// over `n_paths` scenarios split into 20 batches.
+    pub fn new(base_steps: usize, levels: usize, n_paths: usize) -> Self {
+        Self {
+            base_steps,
+            levels,
+            n_paths,
+            n_batches: 20,
+            seed: None,
+        }
+    }
+
+    /// Fixes the seed, so that repeated studies give identical results.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the number of batches used for the confidence intervals.
+    pub fn with_batches(mut self, n_batches: usize) -> Self {
+        self.n_batches = n_batches;
+        self
+    }
+
+    /// Runs the study.
+    ///
+    /// `exact` maps the Brownian increments on the finest grid, of step
+    /// `t_end / (base_steps * 2^(levels - 1))`, to the exact value of the
+    /// process at the time horizon.
+    ///
+    /// Orders are fitted by least squares on `log(error)` against `log(dt)`;
+    /// a discretization that is exact on every grid has zero errors and
+    /// undefined (NaN) orders.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base_steps` is zero, fewer than two levels are requested, or
+    /// there are fewer than two batches or fewer scenarios than batches.
+    pub fn run<P, E>(&self, process: &P, exact: E) -> ConvergenceReport
+    where
+        P: Discretization + ?Sized,
+        E: Fn(&[f64]) -> f64,
+    {
+        assert!(self.base_steps > 0, "base_steps must be at least 1");
+        assert!(self.levels >= 2, "at least two levels are needed");
+        assert!(
+            self.n_batches >= 2 && self.n_paths >= self.n_batches,
+            "need at least 2 batches and one scenario per batch"
+        );
+
+        let t_end = process.time_horizon();
+        let finest = self.base_steps << (self.levels - 1);
+        let fine_dt = t_end / finest as f64;
+        let seed = engine::base_seed(self.seed);
+
+        // Per batch and level: sums of |X_h - X| and of X_h - X.
+        let mut abs_sums = vec![vec![0.0; self.levels]; self.n_batches];
+        let mut diff_sums = vec![vec![0.0; self.levels]; self.n_batches];
+        let mut counts = vec![0usize; self.n_batches];
+        let mut dw = vec![0.0; finest];
+
+        for i in 0..self.n_paths {
+            let mut rng = engine::path_rng(seed, i);
+            for w in dw.iter_mut() {
+                *w = fine_dt.sqrt() * rng.sample::<f64, _>(StandardNormal);
+            }
+            let reference = exact(&dw);
+            let batch = i * self.n_batches / self.n_paths;
+            counts[batch] += 1;
+            for level in 0..self.levels {
+                let diff =
+                    terminal_value(process, &dw, finest >> (self.levels - 1 - level)) - reference;
+                abs_sums[batch][level] += diff.abs();
+                diff_sums[batch][level] += diff;
+            }
+        }
+
+        let dts: Vec<f64> = (0..self.levels)
+            .map(|level| t_end / (self.base_steps << level) as f64)
+            .collect();
+        let n = self.n_paths as f64;
+        let total = |sums: &[Vec<f64>], level: usize| sums.iter().map(|s| s[level]).sum::<f64>();
+        let levels = (0..self.levels)
+            .map(|level| LevelError {
+                n_steps: self.base_steps << level,
+                dt: dts[level],
+                strong_error: total(&abs_sums, level) / n,
+                weak_error: (total(&diff_sums, level) / n).abs(),
+            })
+            .collect::<Vec<_>>();
+
+        // Per batch and level: the strong error is the mean of |X_h - X|, the
+        // weak error the absolute value of the mean of X_h - X.
+        let strong_error =
+            |batch: usize, level: usize| abs_sums[batch][level] / counts[batch] as f64;
+        let weak_error =
+            |batch: usize, level: usize| (diff_sums[batch][level] / counts[batch] as f64).abs();
+        let per_batch = |error: &dyn Fn(usize, usize) -> f64| -> Vec<Vec<f64>> {
+            (0..self.n_batches)
+                .map(|batch| (0..self.levels).map(|level| error(batch, level)).collect())
+                .collect()
+        };
+        let strong: Vec<f64> = levels.iter().map(|l| l.strong_error).collect();
+        let weak: Vec<f64> = levels.iter().map(|l| l.weak_error).collect();
+
+        ConvergenceReport {
+            strong_order: order_estimate(&dts, &strong, &per_batch(&strong_error)),
+            weak_order: order_estimate(&dts, &weak, &per_batch(&weak_error)),
+            levels,
+        }
+    }
+}
+
+/// Steps `process` to the time horizon on a grid of `n_steps` steps, summing
+/// the fine increments `dw` within each step.
+fn terminal_value<P: Discretization + ?Sized>(process: &P, dw: &[f64], n_steps: usize) -> f64 {
+    let dt = process.time_horizon() / n_steps as f64;
+    let per_step = dw.len() / n_steps;
+    dw.chunks_exact(per_step)
+        .enumerate()
+        .fold(process.initial_value(), |x, (j, chunk)| {
+            process.step(j as f64 * dt, x, dt, chunk.iter().sum())
+        })
+}
+
+/// The fitted order on all scenarios, with a normal confidence interval from
+/// the spread of the orders fitted on each batch.
+fn order_estimate(dts: &[f64], errors: &[f64], batches: &[Vec<f64>]) -> OrderEstimate {
+    let order = fitted_slope(dts, errors);
+    let batch_orders: Vec<f64> = batches.iter().map(|e| fitted_slope(dts, e)).collect();
+    let b = batch_orders.len() as f64;
+    let mean = batch_orders.iter().sum::<f64>() / b;
+    let var = batch_orders.iter().map(|o| (o - mean).powi(2)).sum::<f64>() / (b - 1.0);
+    let half_width = Z_95 * (var / b).sqrt();
+    OrderEstimate {
+        order,
+        lower: order - half_width,
+        upper: order + half_width,
+    }
+}
+
+/// Least-squares slope of `log(errors)` against `log(dts)`.
+fn fitted_slope(dts: &[f64], errors: &[f64]) -> f64 {
+    let xs: Vec<f64> = dts.iter().map(|d| d.ln()).collect();
+    let ys: Vec<f64> = errors.iter().map(|e| e.ln()).collect();
+    let n = xs.len() as f64;
+    let (x_mean, y_mean) = (xs.iter().sum::<f64>() / n, ys.iter().sum::<f64>() / n);
+    let cov: f64 = xs
+        .iter()
+        .zip(&ys)
+        .map(|(x, y)| (x - x_mean) * (y - y_mean))
+        .sum();
+    let var: f64 = xs.iter().map(|x| (x - x_mean).powi(2)).sum();
+    cov / var
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArithmeticBrownianMotion, Coefficient, Scheme, Sde};
+
+    #[test]
+    fn test_abm_euler_orders() {
+        let mu = Coefficient::function(|t: f64| t.exp());
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(mu.clone())
+            .sigma(0.5)
+            .s_0(2.0)
+            .scheme(Scheme::EulerMaruyama)
+            .build()
+            .unwrap();
+        let exact = |dw: &[f64]| 2.0 + mu.integral(0.0, 1.0) + 0.5 * dw.iter().sum::<f64>();
+        let report = ConvergenceStudy::new(4, 5, 1_000)
+            .with_seed(3)
+            .run(&abm, exact);
+
+        assert_eq!(report.levels.len(), 5);
+        assert_eq!(report.levels[4].n_steps, 64);
+        // The error is the deterministic drift quadrature error, so both
+        // orders are 1.
+        assert!((report.strong_order.order - 1.0).abs() < 0.05, "{report:?}");
+        assert!((report.weak_order.order - 1.0).abs() < 0.05);
+
+        // The exact scheme has no error beyond rounding.
+        let exact_abm = abm.clone().with_scheme(Scheme::Exact);
+        let report = ConvergenceStudy::new(4, 3, 100).run(&exact_abm, exact);
+        assert!(report.levels.iter().all(|l| l.strong_error < 1e-12));
+    }
+
+    #[test]
+    fn test_gbm_orders_with_confidence_intervals() {
+        let (mu, sigma) = (0.05, 0.4);
+        let exact =
+            |dw: &[f64]| ((mu - 0.5 * sigma * sigma) + sigma * dw.iter().sum::<f64>()).exp();
+        let study = ConvergenceStudy::new(16, 4, 4_000).with_seed(8);
+        let run = |scheme| {
+            let sde = Sde::new(move |_, x| mu * x, move |_, x| sigma * x, 1.0, 1, 1, 1.0)
+                .with_scheme(scheme);
+            study.run(&sde, exact)
+        };
+
+        let euler = run(Scheme::EulerMaruyama);
+        let order = euler.strong_order;
+        assert!((order.order - 0.5).abs() < 0.05, "{order:?}");
+        assert!(order.lower < order.order && order.order < order.upper);
+        assert!(order.upper - order.lower < 0.1);
+
+        let milstein = run(Scheme::Milstein);
+        let order = milstein.strong_order;
+        assert!((order.order - 1.0).abs() < 0.1, "{order:?}");
+        assert!(milstein.levels[3].strong_error < euler.levels[3].strong_error);
+    }
+}
@@ -0,0 +1,78 @@
+//! Path generation shared by the processes.
+//!
+//! Every path gets its own ChaCha8 stream derived from a single base seed, so
+//! path `i` is the same whether paths are generated serially or across any
+//! number of threads (with the `parallel` feature).
+
+use crate::PathMatrix;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The configured seed, or a fresh one from the thread-local generator.
+pub(crate) fn base_seed(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| rand::thread_rng().gen())
+}
+
+/// The generator driving path `index` of a simulation seeded with `seed`.
+pub(crate) fn path_rng(seed: u64, index: usize) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(index as u64);
+    rng
+}
+
+/// Calls `fill(path, rng)` for every row of `paths`, with row `i` driven by `path_rng(seed, i)`.
+pub(crate) fn fill_paths<F>(paths: &mut PathMatrix, seed: u64, fill: F)
+where
+    F: Fn(&mut [f64], &mut ChaCha8Rng) + Sync,
+{
+    let n = paths.n_times();
+    fill_rows(paths.as_mut_slice(), n, seed, fill);
+}
+
+/// Calls `fill(row, rng)` for every `row_len` chunk of a row-major buffer,
+/// with row `i` driven by `path_rng(seed, i)`.
+pub(crate) fn fill_rows<F>(data: &mut [f64], row_len: usize, seed: u64, fill: F)
+where
+    F: Fn(&mut [f64], &mut ChaCha8Rng) + Sync,
+{
+    let run = |(i, row): (usize, &mut [f64])| fill(row, &mut path_rng(seed, i));
+
+    #[cfg(feature = "parallel")]
+    data.par_chunks_exact_mut(row_len).enumerate().for_each(run);
+
+    #[cfg(not(feature = "parallel"))]
+    data.chunks_exact_mut(row_len).enumerate().for_each(run);
+}
+
+/// Like [`fill_paths`] for processes producing two matrices per scenario, such
+/// as price and variance.
+pub(crate) fn fill_path_pairs<F>(
+    first: &mut PathMatrix,
+    second: &mut PathMatrix,
+    seed: u64,
+    fill: F,
+) where
+    F: Fn(&mut [f64], &mut [f64], &mut ChaCha8Rng) + Sync,
+{
+    let (n, m) = (first.n_times(), second.n_times());
+    let run = |(i, (a, b)): (usize, (&mut [f64], &mut [f64]))| fill(a, b, &mut path_rng(seed, i));
+
+    #[cfg(feature = "parallel")]
+    first
+        .as_mut_slice()
+        .par_chunks_exact_mut(n)
+        .zip(second.as_mut_slice().par_chunks_exact_mut(m))
+        .enumerate()
+        .for_each(run);
+
+    #[cfg(not(feature = "parallel"))]
+    first
+        .as_mut_slice()
+        .chunks_exact_mut(n)
+        .zip(second.as_mut_slice().chunks_exact_mut(m))
+        .enumerate()
+        .for_each(run);
+}
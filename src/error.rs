@@ -0,0 +1,82 @@
+use crate::Scheme;
+use std::fmt;
+
+/// Errors reported when an [`ArithmeticBrownianMotion`](crate::ArithmeticBrownianMotion)
+/// or another process of the crate is configured with parameters that cannot produce
+/// meaningful paths.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AbmError {
+    /// A parameter is NaN or infinite.
+    NonFinite { name: &'static str, value: f64 },
+    /// The volatility is negative.
+    NegativeVolatility(f64),
+    /// A parameter that must be non-negative, such as a mean-reversion speed
+    /// or the initial value of a non-negative process, is negative.
+    NegativeParameter { name: &'static str, value: f64 },
+    /// A parameter that must be positive, such as a divisor of the
+    /// discretization, is zero or negative.
+    NonPositiveParameter { name: &'static str, value: f64 },
+    /// `n_steps` is zero, which makes the time step `t_end / n_steps` infinite.
+    ZeroSteps,
+    /// The time horizon `t_end` is zero or negative.
+    NonPositiveHorizon(f64),
+    /// A time-dependent coefficient is malformed.
+    InvalidCoefficient(String),
+    /// An explicit time grid is malformed.
+    InvalidTimeGrid(String),
+    /// The process cannot be simulated with the selected scheme.
+    UnsupportedScheme(Scheme),
+    /// A per-asset parameter does not have one entry per asset.
+    DimensionMismatch {
+        name: &'static str,
+        expected: usize,
+        got: usize,
+    },
+    /// A multi-asset model has no assets.
+    NoAssets,
+    /// A correlation matrix is not a valid correlation matrix.
+    InvalidCorrelation(String),
+    /// Antithetic sampling was requested for an odd number of paths.
+    OddAntitheticPaths(usize),
+}
+
+impl fmt::Display for AbmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbmError::NonFinite { name, value } => {
+                write!(f, "parameter `{name}` must be finite, got {value}")
+            }
+            AbmError::NegativeVolatility(sigma) => {
+                write!(f, "volatility must be non-negative, got {sigma}")
+            }
+            AbmError::NegativeParameter { name, value } => {
+                write!(f, "parameter `{name}` must be non-negative, got {value}")
+            }
+            AbmError::NonPositiveParameter { name, value } => {
+                write!(f, "parameter `{name}` must be positive, got {value}")
+            }
+            AbmError::ZeroSteps => write!(f, "number of steps must be at least 1"),
+            AbmError::NonPositiveHorizon(t_end) => {
+                write!(f, "time horizon must be positive, got {t_end}")
+            }
+            AbmError::InvalidCoefficient(reason) => write!(f, "invalid coefficient: {reason}"),
+            AbmError::InvalidTimeGrid(reason) => write!(f, "invalid time grid: {reason}"),
+            AbmError::UnsupportedScheme(scheme) => {
+                write!(f, "scheme {scheme:?} is not supported by this process")
+            }
+            AbmError::DimensionMismatch {
+                name,
+                expected,
+                got,
+            } => write!(f, "`{name}` must have {expected} entries, got {got}"),
+            AbmError::NoAssets => write!(f, "at least one asset is needed"),
+            AbmError::InvalidCorrelation(reason) => write!(f, "invalid correlation: {reason}"),
+            AbmError::OddAntitheticPaths(n_paths) => write!(
+                f,
+                "antithetic sampling needs an even number of paths, got {n_paths}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AbmError {}
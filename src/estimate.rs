@@ -0,0 +1,52 @@
+use crate::normal::Z_95;
+
+/// A Monte Carlo estimate of an expectation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    /// The sample mean.
+    pub mean: f64,
+    /// The standard error of the mean, `sqrt(sample variance / n_samples)`.
+    pub std_error: f64,
+    /// The number of independent samples behind the estimate.
+    pub n_samples: usize,
+}
+
+impl Estimate {
+    /// The estimate from independent, identically distributed samples.
+    ///
+    /// The standard error is NaN for fewer than two samples.
+    pub fn from_samples(samples: &[f64]) -> Self {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        Self {
+            mean,
+            std_error: (var / n).sqrt(),
+            n_samples: samples.len(),
+        }
+    }
+
+    /// The asymptotic 95% confidence interval `mean +/- 1.96 * std_error`.
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        (
+            self.mean - Z_95 * self.std_error,
+            self.mean + Z_95 * self.std_error,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_from_samples() {
+        let estimate = Estimate::from_samples(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(estimate.mean, 2.5);
+        assert_eq!(estimate.n_samples, 4);
+        // Sample variance 5/3 over 4 samples.
+        assert!((estimate.std_error - (5.0_f64 / 12.0).sqrt()).abs() < 1e-15);
+        let (lo, hi) = estimate.confidence_interval();
+        assert!((hi - lo - 2.0 * Z_95 * estimate.std_error).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{engine, PathMatrix, StochasticProcess};
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+/// The Geometric Brownian Motion (GBM) model simulates the price movement
+/// of an asset over time using the following formula:
+///
+/// dS = mu * S * dt + sigma * S * d_w
+///
+/// Where:
+/// - `mu` is the drift (expected return)
+/// - `sigma` is the volatility (standard deviation of returns)
+/// - `d_w` is a Wiener process increment (Brownian motion), distributed as N(0, dt)
+///
+/// Unlike the arithmetic model, prices stay strictly positive when `s_0 > 0`.
+pub struct GeometricBrownianMotion {
+    pub mu: f64,
+    pub sigma: f64,
+    pub n_paths: usize,
+    pub n_steps: usize,
+    pub t_end: f64,
+    pub s_0: f64,
+    /// Optional seed of the per-path random streams, see
+    /// [reproducibility](crate#reproducibility).
+    pub seed: Option<u64>,
+}
+
+impl GeometricBrownianMotion {
+    /// Creates a new instance of the Geometric Brownian Motion model.
+    ///
+    /// # Arguments
+    ///
+    /// * `mu` - The drift (mean) of the asset's returns.
+    /// * `sigma` - The volatility (standard deviation) of the asset's returns.
+    /// * `n_paths` - Number of simulated paths.
+    /// * `n_steps` - Number of steps in each path.
+    /// * `t_end` - Total time of simulation.
+    /// * `s_0` - Initial value of the asset (price at t=0).
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `GeometricBrownianMotion`.
+    pub fn new(mu: f64, sigma: f64, n_paths: usize, n_steps: usize, t_end: f64, s_0: f64) -> Self {
+        Self {
+            mu,
+            sigma,
+            n_paths,
+            n_steps,
+            t_end,
+            s_0,
+            seed: None,
+        }
+    }
+
+    /// Fixes the seed used by `simulate`, so that repeated runs produce
+    /// bit-identical paths.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Simulates the asset price paths using the exact log-normal transition.
+    ///
+    /// Each step applies
+    ///
+    /// S(t + dt) = S(t) * exp((mu - sigma^2 / 2) * dt + sigma * d_w)
+    ///
+    /// which has no discretization error, so `n_steps` only controls the
+    /// observation grid.
+    ///
+    /// # Returns
+    ///
+    /// A `PathMatrix` whose rows are the simulated paths of asset prices.
+    ///
+    /// Each path has `n_steps + 1` values, including the initial value `s_0`.
+    pub fn simulate(&self) -> PathMatrix {
+        let mut paths = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.s_0);
+        engine::fill_paths(&mut paths, engine::base_seed(self.seed), |path, rng| {
+            self.fill_path(path, rng)
+        });
+        paths
+    }
+
+    /// Simulates the asset price paths using a caller-supplied random number generator.
+    ///
+    /// The `seed` field is ignored; the paths are fully determined by the state of `rng`.
+    pub fn simulate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> PathMatrix {
+        let mut paths = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.s_0);
+        for path in paths.iter_mut() {
+            self.fill_path(path, rng);
+        }
+        paths
+    }
+
+    /// Simulates a single asset price path of `n_steps + 1` values.
+    pub fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        let mut path = vec![self.s_0; self.n_steps + 1];
+        self.fill_path(&mut path, rng);
+        path
+    }
+
+    /// Writes one path into `path`, whose length sets the number of steps.
+    fn fill_path<R: Rng + ?Sized>(&self, path: &mut [f64], rng: &mut R) {
+        let dt = self.t_end / self.n_steps as f64;
+        let log_drift = (self.mu - 0.5 * self.sigma * self.sigma) * dt;
+        let vol = self.sigma * dt.sqrt();
+        path[0] = self.s_0;
+
+        for j in 1..path.len() {
+            let z: f64 = rng.sample(StandardNormal);
+            path[j] = path[j - 1] * (log_drift + vol * z).exp();
+        }
+    }
+
+    /// The analytic mean of S(t): `s_0 * exp(mu * t)`.
+    pub fn mean(&self, t: f64) -> f64 {
+        self.s_0 * (self.mu * t).exp()
+    }
+
+    /// The analytic variance of S(t): `s_0^2 * exp(2 * mu * t) * (exp(sigma^2 * t) - 1)`.
+    pub fn variance(&self, t: f64) -> f64 {
+        self.s_0 * self.s_0 * (2.0 * self.mu * t).exp() * (self.sigma * self.sigma * t).exp_m1()
+    }
+}
+
+impl StochasticProcess for GeometricBrownianMotion {
+    fn drift(&self, _t: f64, x: f64) -> f64 {
+        self.mu * x
+    }
+
+    fn diffusion(&self, _t: f64, x: f64) -> f64 {
+        self.sigma * x
+    }
+
+    fn initial_value(&self) -> f64 {
+        self.s_0
+    }
+
+    fn time_horizon(&self) -> f64 {
+        self.t_end
+    }
+
+    fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        GeometricBrownianMotion::sample_path(self, rng)
+    }
+
+    fn simulate(&self) -> PathMatrix {
+        GeometricBrownianMotion::simulate(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mean_and_variance;
+
+    #[test]
+    fn test_gbm_simulation() {
+        let gbm = GeometricBrownianMotion::new(0.05, 0.4, 50, 200, 1.0, 200.0).with_seed(1);
+        let paths = gbm.simulate();
+        assert_eq!(paths.len(), 50);
+        assert_eq!(paths[0].len(), 201);
+        assert!(paths.iter().flatten().all(|&s| s > 0.0));
+    }
+
+    #[test]
+    fn test_gbm_log_moments() {
+        // ln S(T) ~ N(ln s_0 + (mu - sigma^2 / 2) * T, sigma^2 * T)
+        let (mu, sigma, t_end, s_0) = (0.1, 0.3, 2.0, 50.0);
+        let n_paths = 20_000;
+        let gbm = GeometricBrownianMotion::new(mu, sigma, n_paths, 8, t_end, s_0).with_seed(11);
+        let logs: Vec<f64> = gbm
+            .simulate()
+            .terminal_values()
+            .iter()
+            .map(|s| s.ln())
+            .collect();
+        let (mean, var) = mean_and_variance(&logs);
+
+        let expected_mean = s_0.ln() + (mu - 0.5 * sigma * sigma) * t_end;
+        let expected_var = sigma * sigma * t_end;
+        let n = n_paths as f64;
+        assert!((mean - expected_mean).abs() < 5.0 * (expected_var / n).sqrt());
+        assert!((var - expected_var).abs() < 5.0 * expected_var * (2.0 / (n - 1.0)).sqrt());
+    }
+
+    #[test]
+    fn test_gbm_terminal_moments() {
+        let (mu, sigma, t_end, s_0) = (0.08, 0.25, 1.5, 100.0);
+        let n_paths = 50_000;
+        let gbm = GeometricBrownianMotion::new(mu, sigma, n_paths, 4, t_end, s_0).with_seed(5);
+        let (mean, var) = mean_and_variance(&gbm.simulate().terminal_values());
+
+        let std_err = (gbm.variance(t_end) / n_paths as f64).sqrt();
+        assert!((mean - gbm.mean(t_end)).abs() < 5.0 * std_err);
+        // The sample variance of a log-normal is noisier than the Gaussian case; 5% is ample here.
+        assert!((var / gbm.variance(t_end) - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_gbm_step_count_does_not_bias() {
+        // The exact scheme gives the same terminal distribution for one step or many.
+        let (mu, sigma) = (0.2, 0.5);
+        let one = GeometricBrownianMotion::new(mu, sigma, 20_000, 1, 1.0, 1.0).with_seed(2);
+        let many = GeometricBrownianMotion::new(mu, sigma, 20_000, 100, 1.0, 1.0).with_seed(3);
+        let (m1, _) = mean_and_variance(&one.simulate().terminal_values());
+        let (m2, _) = mean_and_variance(&many.simulate().terminal_values());
+        let std_err = (2.0 * one.variance(1.0) / 20_000.0).sqrt();
+        assert!((m1 - m2).abs() < 5.0 * std_err);
+    }
+}
@@ -0,0 +1,530 @@
+use crate::cir::QeBranch;
+use crate::{engine, AbmError, PathMatrix};
+use num_complex::Complex64;
+use rand::Rng;
+use rand_distr::StandardNormal;
+use std::f64::consts::PI;
+
+/// Weights of the variance integral `int_t^{t+dt} V(s) ds ~ (gamma_1 * V(t) + gamma_2 * V(t+dt)) * dt`
+/// used by the log-price step (central discretization).
+const GAMMA_1: f64 = 0.5;
+const GAMMA_2: f64 = 0.5;
+
+/// Upper limit and number of Simpson intervals for the Fourier integrals in
+/// [`Heston::call_price`].
+const FOURIER_UPPER_LIMIT: f64 = 200.0;
+const FOURIER_INTERVALS: usize = 4_000;
+
+/// The Heston stochastic volatility model simulates correlated price and
+/// variance paths using the following formulas:
+///
+/// dS = mu * S * dt + sqrt(V) * S * d_w1
+///
+/// dV = kappa * (theta - V) * dt + xi * sqrt(V) * d_w2
+///
+/// Where:
+/// - `mu` is the drift of the price (the risk-free rate when pricing)
+/// - `kappa` is the speed of mean reversion of the variance
+/// - `theta` is the long-run variance
+/// - `xi` is the volatility of the variance
+/// - `d_w1` and `d_w2` are Wiener process increments with correlation `rho`
+pub struct Heston {
+    pub mu: f64,
+    pub kappa: f64,
+    pub theta: f64,
+    pub xi: f64,
+    pub rho: f64,
+    pub v_0: f64,
+    pub n_paths: usize,
+    pub n_steps: usize,
+    pub t_end: f64,
+    pub s_0: f64,
+    /// Optional seed of the per-path random streams, see
+    /// [reproducibility](crate#reproducibility).
+    pub seed: Option<u64>,
+}
+
+/// Simulated Heston paths. Row `i` of `prices` and row `i` of `variances`
+/// belong to the same scenario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HestonPaths {
+    /// Asset price paths, each with `n_steps + 1` values starting at `s_0`.
+    pub prices: PathMatrix,
+    /// Instantaneous variance paths, each with `n_steps + 1` values starting at `v_0`.
+    pub variances: PathMatrix,
+}
+
+impl Heston {
+    /// Creates a new instance of the Heston model.
+    ///
+    /// # Arguments
+    ///
+    /// * `mu` - The drift of the asset price.
+    /// * `kappa` - The speed of mean reversion of the variance.
+    /// * `theta` - The long-run variance.
+    /// * `xi` - The volatility of the variance.
+    /// * `rho` - The correlation between the price and variance shocks.
+    /// * `v_0` - Initial variance (at t=0).
+    /// * `n_paths` - Number of simulated paths.
+    /// * `n_steps` - Number of steps in each path.
+    /// * `t_end` - Total time of simulation.
+    /// * `s_0` - Initial value of the asset (price at t=0).
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `Heston`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        mu: f64,
+        kappa: f64,
+        theta: f64,
+        xi: f64,
+        rho: f64,
+        v_0: f64,
+        n_paths: usize,
+        n_steps: usize,
+        t_end: f64,
+        s_0: f64,
+    ) -> Self {
+        Self {
+            mu,
+            kappa,
+            theta,
+            xi,
+            rho,
+            v_0,
+            n_paths,
+            n_steps,
+            t_end,
+            s_0,
+            seed: None,
+        }
+    }
+
+    /// Like `new`, but validates the parameters.
+    ///
+    /// # Errors
+    ///
+    /// See `validate`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        mu: f64,
+        kappa: f64,
+        theta: f64,
+        xi: f64,
+        rho: f64,
+        v_0: f64,
+        n_paths: usize,
+        n_steps: usize,
+        t_end: f64,
+        s_0: f64,
+    ) -> Result<Self, AbmError> {
+        let model = Self::new(mu, kappa, theta, xi, rho, v_0, n_paths, n_steps, t_end, s_0);
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// Checks that the current parameters describe a well-defined simulation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AbmError` if a parameter is not finite, `xi` is not
+    /// positive (the log-price step divides by it), `rho` lies outside
+    /// `[-1, 1]`, `kappa`, `theta` or `v_0` is negative, `n_steps` is zero,
+    /// or `t_end` is not positive.
+    pub fn validate(&self) -> Result<(), AbmError> {
+        for (name, value) in [
+            ("mu", self.mu),
+            ("kappa", self.kappa),
+            ("theta", self.theta),
+            ("xi", self.xi),
+            ("rho", self.rho),
+            ("v_0", self.v_0),
+            ("t_end", self.t_end),
+            ("s_0", self.s_0),
+        ] {
+            if !value.is_finite() {
+                return Err(AbmError::NonFinite { name, value });
+            }
+        }
+        if self.xi <= 0.0 {
+            return Err(AbmError::NonPositiveParameter {
+                name: "xi",
+                value: self.xi,
+            });
+        }
+        if self.rho.abs() > 1.0 {
+            return Err(AbmError::InvalidCorrelation(format!(
+                "rho must lie in [-1, 1], got {}",
+                self.rho
+            )));
+        }
+        for (name, value) in [
+            ("kappa", self.kappa),
+            ("theta", self.theta),
+            ("v_0", self.v_0),
+        ] {
+            if value < 0.0 {
+                return Err(AbmError::NegativeParameter { name, value });
+            }
+        }
+        if self.n_steps == 0 {
+            return Err(AbmError::ZeroSteps);
+        }
+        if self.t_end <= 0.0 {
+            return Err(AbmError::NonPositiveHorizon(self.t_end));
+        }
+        Ok(())
+    }
+
+    /// Panics with a descriptive message if the parameters are invalid.
+    fn assert_valid(&self) {
+        if let Err(err) = self.validate() {
+            panic!("invalid Heston: {err}");
+        }
+    }
+
+    /// Fixes the seed used by `simulate`, so that repeated runs produce
+    /// bit-identical paths.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Simulates price and variance paths with Andersen's quadratic-exponential
+    /// (QE) scheme.
+    ///
+    /// The variance is advanced with the moment-matched QE step, and the log
+    /// price with
+    ///
+    /// ln S(t+dt) = ln S(t) + mu * dt + K0* + K1 * V(t) + K2 * V(t+dt) + sqrt(K3 * V(t) + K4 * V(t+dt)) * Z
+    ///
+    /// where `K0*` is chosen so that `E[S(t+dt) | S(t)] = S(t) * exp(mu * dt)`
+    /// holds exactly (martingale correction).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`); use
+    /// `try_simulate` to handle this as an error instead.
+    pub fn simulate(&self) -> HestonPaths {
+        self.assert_valid();
+        let mut prices = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.s_0);
+        let mut variances = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.v_0);
+        engine::fill_path_pairs(
+            &mut prices,
+            &mut variances,
+            engine::base_seed(self.seed),
+            |price, variance, rng| self.fill_path(price, variance, rng),
+        );
+        HestonPaths { prices, variances }
+    }
+
+    /// Simulates price and variance paths like `simulate`, returning an error
+    /// instead of panicking when the parameters are invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `AbmError` reported by `validate`.
+    pub fn try_simulate(&self) -> Result<HestonPaths, AbmError> {
+        self.validate()?;
+        Ok(self.simulate())
+    }
+
+    /// Simulates price and variance paths using a caller-supplied random number generator.
+    ///
+    /// The `seed` field is ignored; the paths are fully determined by the state of `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn simulate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> HestonPaths {
+        self.assert_valid();
+        let mut prices = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.s_0);
+        let mut variances = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.v_0);
+        for (price, variance) in prices.iter_mut().zip(variances.iter_mut()) {
+            self.fill_path(price, variance, rng);
+        }
+        HestonPaths { prices, variances }
+    }
+
+    /// Simulates a single scenario, returning the price and variance paths.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> (Vec<f64>, Vec<f64>) {
+        self.assert_valid();
+        let mut prices = vec![self.s_0; self.n_steps + 1];
+        let mut variances = vec![self.v_0; self.n_steps + 1];
+        self.fill_path(&mut prices, &mut variances, rng);
+        (prices, variances)
+    }
+
+    /// Writes one scenario into `prices` and `variances`, whose common length
+    /// sets the number of steps.
+    fn fill_path<R: Rng + ?Sized>(&self, prices: &mut [f64], variances: &mut [f64], rng: &mut R) {
+        let dt = self.t_end / self.n_steps as f64;
+        prices[0] = self.s_0;
+        variances[0] = self.v_0;
+
+        let k = self.rho / self.xi;
+        let k0 = -k * self.kappa * self.theta * dt;
+        let k1 = GAMMA_1 * dt * (self.kappa * k - 0.5) - k;
+        let k2 = GAMMA_2 * dt * (self.kappa * k - 0.5) + k;
+        let k3 = GAMMA_1 * dt * (1.0 - self.rho * self.rho);
+        let k4 = GAMMA_2 * dt * (1.0 - self.rho * self.rho);
+        let a_coef = k2 + 0.5 * k4;
+
+        let mut log_s = self.s_0.ln();
+        for j in 1..prices.len() {
+            let v = variances[j - 1];
+            let branch = QeBranch::new(v, self.kappa, self.theta, self.xi, dt);
+            let v_next = branch.sample(rng);
+
+            let k0_star = martingale_k0(&branch, a_coef)
+                .map(|c| c - (k1 + 0.5 * k3) * v)
+                .unwrap_or(k0);
+            let z: f64 = rng.sample(StandardNormal);
+            log_s += self.mu * dt
+                + k0_star
+                + k1 * v
+                + k2 * v_next
+                + (k3 * v + k4 * v_next).max(0.0).sqrt() * z;
+
+            variances[j] = v_next;
+            prices[j] = log_s.exp();
+        }
+    }
+
+    /// Semi-analytic price of a European call option struck at `strike` and
+    /// expiring at `t_end`, treating `mu` as the continuously compounded
+    /// risk-free rate.
+    ///
+    /// Uses the Heston (1993) formula `C = s_0 * P1 - K * exp(-mu * T) * P2`,
+    /// with the probabilities `P1` and `P2` obtained by Fourier inversion of the
+    /// characteristic function in the numerically stable form of Albrecher et al.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid (see `validate`).
+    pub fn call_price(&self, strike: f64) -> f64 {
+        self.assert_valid();
+        let ln_k = strike.ln();
+        let i = Complex64::i();
+        // ln phi(-i) = ln E[S_T] = ln s_0 + mu * T
+        let ln_forward = self.s_0.ln() + self.mu * self.t_end;
+
+        let p1 = probability(|u| {
+            let ln_phi = self.log_characteristic_function(Complex64::new(u, -1.0));
+            ((ln_phi - ln_forward - i * u * ln_k).exp() / (i * u)).re
+        });
+        let p2 = probability(|u| {
+            let ln_phi = self.log_characteristic_function(Complex64::new(u, 0.0));
+            ((ln_phi - i * u * ln_k).exp() / (i * u)).re
+        });
+
+        self.s_0 * p1 - strike * (-self.mu * self.t_end).exp() * p2
+    }
+
+    /// `ln E[exp(i * u * ln S(T))]` for complex `u`.
+    fn log_characteristic_function(&self, u: Complex64) -> Complex64 {
+        let i = Complex64::i();
+        let t = self.t_end;
+        let xi2 = self.xi * self.xi;
+        let iu = i * u;
+
+        let beta = self.kappa - self.rho * self.xi * iu;
+        let d = (beta * beta + xi2 * (u * u + iu)).sqrt();
+        let g = (beta - d) / (beta + d);
+        let e = (-d * t).exp();
+
+        let c = self.mu * iu * t
+            + self.kappa * self.theta / xi2
+                * ((beta - d) * t - 2.0 * ((1.0 - g * e) / (1.0 - g)).ln());
+        let dv = (beta - d) / xi2 * (1.0 - e) / (1.0 - g * e);
+        c + dv * self.v_0 + iu * self.s_0.ln()
+    }
+}
+
+/// The part of the martingale-corrected `K0*` that does not depend on `V(t)`:
+/// `-ln E[exp(A * V(t+dt)) | V(t)]`.
+///
+/// Returns `None` when the moment generating function does not exist, in which
+/// case the uncorrected `K0` is used.
+fn martingale_k0(branch: &QeBranch, a_coef: f64) -> Option<f64> {
+    match *branch {
+        QeBranch::Quadratic { a, b2 } => {
+            let denom = 1.0 - 2.0 * a_coef * a;
+            (denom > 0.0).then(|| -a_coef * b2 * a / denom + 0.5 * denom.ln())
+        }
+        QeBranch::Exponential { p, beta } => {
+            (a_coef < beta).then(|| -(p + beta * (1.0 - p) / (beta - a_coef)).ln())
+        }
+    }
+}
+
+/// `1/2 + 1/pi * int_0^inf integrand(u) du`, truncated and evaluated with
+/// composite Simpson's rule. The integrand is singular-looking but finite at
+/// zero, so the first node is nudged away from it.
+fn probability<F: Fn(f64) -> f64>(integrand: F) -> f64 {
+    let h = FOURIER_UPPER_LIMIT / FOURIER_INTERVALS as f64;
+    let sum: f64 = (0..=FOURIER_INTERVALS)
+        .map(|j| {
+            let u = (j as f64 * h).max(1e-10);
+            let weight = if j == 0 || j == FOURIER_INTERVALS {
+                1.0
+            } else if j % 2 == 1 {
+                4.0
+            } else {
+                2.0
+            };
+            weight * integrand(u)
+        })
+        .sum();
+    0.5 + sum * h / (3.0 * PI)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mean_and_variance;
+
+    fn model(n_paths: usize, n_steps: usize) -> Heston {
+        Heston::new(
+            0.03, 2.0, 0.04, 0.5, -0.7, 0.04, n_paths, n_steps, 1.0, 100.0,
+        )
+    }
+
+    #[test]
+    fn test_heston_simulation() {
+        let paths = model(50, 200).with_seed(1).simulate();
+        assert_eq!(paths.prices.len(), 50);
+        assert_eq!(paths.variances.len(), 50);
+        assert_eq!(paths.prices[0].len(), 201);
+        assert_eq!(paths.variances[0].len(), 201);
+        assert!(paths.prices.iter().flatten().all(|&s| s > 0.0));
+        assert!(paths.variances.iter().flatten().all(|&v| v >= 0.0));
+    }
+
+    #[test]
+    fn test_heston_martingale_correction() {
+        // Even with very coarse steps the discounted price stays a martingale.
+        let heston = model(40_000, 4).with_seed(3);
+        let discount = (-heston.mu * heston.t_end).exp();
+        let discounted: Vec<f64> = heston
+            .simulate()
+            .prices
+            .iter()
+            .map(|p| p.last().unwrap() * discount)
+            .collect();
+        let (mean, var) = mean_and_variance(&discounted);
+        let std_err = (var / discounted.len() as f64).sqrt();
+        assert!(
+            (mean - heston.s_0).abs() < 5.0 * std_err,
+            "{mean} +- {std_err}"
+        );
+    }
+
+    #[test]
+    fn test_heston_call_price_bounds() {
+        let heston = model(1, 1);
+        let discount = (-heston.mu * heston.t_end).exp();
+        let mut previous = f64::INFINITY;
+        for strike in [60.0, 80.0, 100.0, 120.0, 140.0] {
+            let price = heston.call_price(strike);
+            assert!(price >= (heston.s_0 - strike * discount).max(0.0) - 1e-8);
+            assert!(price <= heston.s_0);
+            assert!(price < previous);
+            previous = price;
+        }
+    }
+
+    #[test]
+    fn test_heston_call_price_matches_monte_carlo() {
+        let heston = model(40_000, 50).with_seed(7);
+        let discount = (-heston.mu * heston.t_end).exp();
+        let paths = heston.simulate();
+        for strike in [90.0, 100.0, 110.0] {
+            let payoffs: Vec<f64> = paths
+                .prices
+                .iter()
+                .map(|p| discount * (p.last().unwrap() - strike).max(0.0))
+                .collect();
+            let (mc, var) = mean_and_variance(&payoffs);
+            let std_err = (var / payoffs.len() as f64).sqrt();
+            let analytic = heston.call_price(strike);
+            assert!(
+                (mc - analytic).abs() < 5.0 * std_err + 0.02,
+                "K={strike}: MC {mc} +- {std_err}, analytic {analytic}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_heston_negative_correlation() {
+        // Log-price and variance increments move in opposite directions for rho < 0.
+        let paths = model(500, 100).with_seed(11).simulate();
+        let pairs: Vec<(f64, f64)> = paths
+            .prices
+            .iter()
+            .zip(&paths.variances)
+            .flat_map(|(s, v)| {
+                s.windows(2)
+                    .zip(v.windows(2))
+                    .map(|(ws, wv)| ((ws[1] / ws[0]).ln(), wv[1] - wv[0]))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let n = pairs.len() as f64;
+        let (mx, my) = pairs
+            .iter()
+            .fold((0.0, 0.0), |acc, (x, y)| (acc.0 + x / n, acc.1 + y / n));
+        let cov: f64 = pairs.iter().map(|(x, y)| (x - mx) * (y - my)).sum::<f64>();
+        assert!(cov < 0.0);
+    }
+
+    #[test]
+    fn test_heston_validate() {
+        let params = |xi: f64, rho: f64, kappa: f64| {
+            Heston::try_new(0.03, kappa, 0.04, xi, rho, 0.04, 1, 10, 1.0, 100.0)
+        };
+        assert!(params(0.5, -0.7, 2.0).is_ok());
+        assert_eq!(
+            params(0.0, -0.7, 2.0).err(),
+            Some(AbmError::NonPositiveParameter {
+                name: "xi",
+                value: 0.0
+            })
+        );
+        assert!(matches!(
+            params(0.5, -1.5, 2.0),
+            Err(AbmError::InvalidCorrelation(_))
+        ));
+        assert_eq!(
+            params(0.5, -0.7, -2.0).err(),
+            Some(AbmError::NegativeParameter {
+                name: "kappa",
+                value: -2.0
+            })
+        );
+        assert!(matches!(
+            params(f64::NAN, -0.7, 2.0),
+            Err(AbmError::NonFinite { name: "xi", .. })
+        ));
+
+        let mut heston = model(1, 10);
+        heston.xi = 0.0;
+        assert!(heston.try_simulate().is_err());
+        heston.xi = 0.5;
+        heston.n_steps = 0;
+        assert_eq!(heston.try_simulate().err(), Some(AbmError::ZeroSteps));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid Heston: parameter `xi` must be positive")]
+    fn test_heston_simulate_rejects_zero_xi() {
+        let mut heston = model(1, 10);
+        heston.xi = 0.0;
+        let _ = heston.simulate();
+    }
+}
@@ -0,0 +1,464 @@
+use crate::normal;
+use crate::{engine, PathMatrix, StochasticProcess};
+use rand::Rng;
+use rand_distr::{Distribution, Poisson, StandardNormal};
+
+/// Probability mass of the Poisson jump count left out when truncating the
+/// series expansions of the terminal distribution.
+const SERIES_TOLERANCE: f64 = 1e-14;
+
+/// The continuous part that [`MertonJumpDiffusion`] adds jumps to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JumpDynamics {
+    /// Jumps are added to an arithmetic Brownian motion:
+    ///
+    /// dS = mu * dt + sigma * d_w + d_j
+    Arithmetic,
+    /// Jumps are added to the log of a geometric Brownian motion, with the
+    /// drift compensated so that `E[S(t)] = s_0 * exp(mu * t)`:
+    ///
+    /// dS / S = (mu - lambda * k) * dt + sigma * d_w + (e^Y - 1) * dN, with `k = E[e^Y] - 1`
+    Geometric,
+}
+
+/// The Merton jump-diffusion model adds compound Poisson jumps to Brownian
+/// dynamics:
+///
+/// d_j = sum of the jump sizes `Y_k ~ N(jump_mean, jump_std^2)` arriving in `dt`
+///
+/// Where:
+/// - `mu` is the drift (expected return)
+/// - `sigma` is the volatility of the diffusive part
+/// - `lambda` is the jump intensity (expected number of jumps per unit time)
+/// - `jump_mean` and `jump_std` describe the normal jump sizes
+///
+/// Jumps act on the price itself or on its logarithm depending on `dynamics`.
+pub struct MertonJumpDiffusion {
+    pub mu: f64,
+    pub sigma: f64,
+    pub lambda: f64,
+    pub jump_mean: f64,
+    pub jump_std: f64,
+    pub n_paths: usize,
+    pub n_steps: usize,
+    pub t_end: f64,
+    pub s_0: f64,
+    /// Whether jumps act on an arithmetic or geometric Brownian motion,
+    /// `JumpDynamics::Arithmetic` by default.
+    pub dynamics: JumpDynamics,
+    /// Optional seed of the per-path random streams, see
+    /// [reproducibility](crate#reproducibility).
+    pub seed: Option<u64>,
+}
+
+impl MertonJumpDiffusion {
+    /// Creates a new instance of the jump-diffusion model with arithmetic dynamics.
+    ///
+    /// # Arguments
+    ///
+    /// * `mu` - The drift (mean) of the asset's returns.
+    /// * `sigma` - The volatility of the diffusive part.
+    /// * `lambda` - The jump intensity.
+    /// * `jump_mean` - The mean of the normal jump sizes.
+    /// * `jump_std` - The standard deviation of the normal jump sizes.
+    /// * `n_paths` - Number of simulated paths.
+    /// * `n_steps` - Number of steps in each path.
+    /// * `t_end` - Total time of simulation.
+    /// * `s_0` - Initial value of the asset (price at t=0).
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `MertonJumpDiffusion`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        mu: f64,
+        sigma: f64,
+        lambda: f64,
+        jump_mean: f64,
+        jump_std: f64,
+        n_paths: usize,
+        n_steps: usize,
+        t_end: f64,
+        s_0: f64,
+    ) -> Self {
+        Self {
+            mu,
+            sigma,
+            lambda,
+            jump_mean,
+            jump_std,
+            n_paths,
+            n_steps,
+            t_end,
+            s_0,
+            dynamics: JumpDynamics::Arithmetic,
+            seed: None,
+        }
+    }
+
+    /// Selects whether jumps act on an arithmetic or geometric Brownian motion.
+    pub fn with_dynamics(mut self, dynamics: JumpDynamics) -> Self {
+        self.dynamics = dynamics;
+        self
+    }
+
+    /// Fixes the seed used by `simulate`, so that repeated runs produce
+    /// bit-identical paths.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Simulates the asset price paths exactly on the time grid.
+    ///
+    /// Each step draws the number of jumps `N ~ Poisson(lambda * dt)` and,
+    /// given `N`, the total jump size `~ N(N * jump_mean, N * jump_std^2)`, so
+    /// there is no discretization error in either component.
+    ///
+    /// # Returns
+    ///
+    /// A `PathMatrix` whose rows are the simulated paths of asset prices.
+    ///
+    /// Each path has `n_steps + 1` values, including the initial value `s_0`.
+    pub fn simulate(&self) -> PathMatrix {
+        let mut paths = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.s_0);
+        engine::fill_paths(&mut paths, engine::base_seed(self.seed), |path, rng| {
+            self.fill_path(path, rng)
+        });
+        paths
+    }
+
+    /// Simulates the asset price paths using a caller-supplied random number generator.
+    ///
+    /// The `seed` field is ignored; the paths are fully determined by the state of `rng`.
+    pub fn simulate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> PathMatrix {
+        let mut paths = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.s_0);
+        for path in paths.iter_mut() {
+            self.fill_path(path, rng);
+        }
+        paths
+    }
+
+    /// Simulates a single asset price path of `n_steps + 1` values.
+    pub fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        let mut path = vec![self.s_0; self.n_steps + 1];
+        self.fill_path(&mut path, rng);
+        path
+    }
+
+    /// Writes one path into `path`, whose length sets the number of steps.
+    fn fill_path<R: Rng + ?Sized>(&self, path: &mut [f64], rng: &mut R) {
+        let dt = self.t_end / self.n_steps as f64;
+        let jump_count = (self.lambda > 0.0).then(|| Poisson::new(self.lambda * dt).unwrap());
+        let vol = self.sigma * dt.sqrt();
+        path[0] = self.s_0;
+
+        // Arithmetic dynamics evolve the price, geometric ones its logarithm.
+        let (mut state, drift) = match self.dynamics {
+            JumpDynamics::Arithmetic => (self.s_0, self.mu * dt),
+            JumpDynamics::Geometric => (self.s_0.ln(), self.log_drift() * dt),
+        };
+
+        for value in path.iter_mut().skip(1) {
+            let z: f64 = rng.sample(StandardNormal);
+            state += drift + vol * z;
+
+            let n = jump_count.map_or(0.0, |poisson| poisson.sample(rng));
+            if n > 0.0 {
+                let z_jump: f64 = rng.sample(StandardNormal);
+                state += n * self.jump_mean + n.sqrt() * self.jump_std * z_jump;
+            }
+
+            *value = match self.dynamics {
+                JumpDynamics::Arithmetic => state,
+                JumpDynamics::Geometric => state.exp(),
+            };
+        }
+    }
+
+    /// The compensator `k = E[e^Y] - 1` of the geometric dynamics.
+    fn jump_compensator(&self) -> f64 {
+        (self.jump_mean + 0.5 * self.jump_std * self.jump_std).exp_m1()
+    }
+
+    /// The drift of `ln S` between jumps under geometric dynamics.
+    fn log_drift(&self) -> f64 {
+        self.mu - 0.5 * self.sigma * self.sigma - self.lambda * self.jump_compensator()
+    }
+
+    /// The analytic mean of S(t).
+    pub fn mean(&self, t: f64) -> f64 {
+        match self.dynamics {
+            JumpDynamics::Arithmetic => self.s_0 + (self.mu + self.lambda * self.jump_mean) * t,
+            JumpDynamics::Geometric => self.s_0 * (self.mu * t).exp(),
+        }
+    }
+
+    /// The analytic variance of S(t).
+    pub fn variance(&self, t: f64) -> f64 {
+        let (m, s2) = (self.jump_mean, self.jump_std * self.jump_std);
+        match self.dynamics {
+            JumpDynamics::Arithmetic => (self.sigma * self.sigma + self.lambda * (m * m + s2)) * t,
+            JumpDynamics::Geometric => {
+                // E[S^2] = s_0^2 * exp(2 * a * t + 2 * sigma^2 * t + lambda * t * (E[e^{2Y}] - 1))
+                let second_moment = (2.0 * self.log_drift() * t
+                    + 2.0 * self.sigma * self.sigma * t
+                    + self.lambda * t * (2.0 * m + 2.0 * s2).exp_m1())
+                .exp();
+                self.s_0 * self.s_0 * (second_moment - (2.0 * self.mu * t).exp())
+            }
+        }
+    }
+
+    /// The density of S(t_end), evaluated as the Poisson mixture of Gaussians
+    /// (log-normals under geometric dynamics) conditional on the jump count.
+    ///
+    /// With `sigma = 0` the no-jump term is a point mass, so the density is
+    /// infinite at the drift point, like that of a deterministic
+    /// [`ArithmeticBrownianMotion`](crate::ArithmeticBrownianMotion).
+    pub fn terminal_density(&self, x: f64) -> f64 {
+        match self.dynamics {
+            JumpDynamics::Arithmetic => self.mixture(x, normal_density),
+            JumpDynamics::Geometric if x > 0.0 => self.mixture(x.ln(), normal_density) / x,
+            JumpDynamics::Geometric => 0.0,
+        }
+    }
+
+    /// The cumulative distribution function of S(t_end), evaluated with the same
+    /// series expansion as [`MertonJumpDiffusion::terminal_density`].
+    pub fn terminal_cdf(&self, x: f64) -> f64 {
+        match self.dynamics {
+            JumpDynamics::Arithmetic => self.mixture(x, normal_cdf),
+            JumpDynamics::Geometric if x > 0.0 => self.mixture(x.ln(), normal_cdf),
+            JumpDynamics::Geometric => 0.0,
+        }
+    }
+
+    /// `sum_n P(N = n) * f(y; mean_n, var_n)` over the Poisson number of jumps
+    /// up to `t_end`, where `y` is the price (arithmetic) or log price (geometric).
+    ///
+    /// The sum starts at the Poisson mode, whose weight is computed in log space,
+    /// and extends outwards in both directions, so that large intensities do not
+    /// underflow `exp(-lambda * t_end)`.
+    fn mixture(&self, y: f64, f: fn(f64, f64, f64) -> f64) -> f64 {
+        let t = self.t_end;
+        let base_mean = match self.dynamics {
+            JumpDynamics::Arithmetic => self.s_0 + self.mu * t,
+            JumpDynamics::Geometric => self.s_0.ln() + self.log_drift() * t,
+        };
+        let base_var = self.sigma * self.sigma * t;
+        let intensity = self.lambda * t;
+        let term = |n: f64, weight: f64| {
+            let mean = base_mean + n * self.jump_mean;
+            let var = base_var + n * self.jump_std * self.jump_std;
+            weight * f(y, mean, var)
+        };
+
+        let mode = intensity.floor();
+        let mode_weight = if mode == 0.0 {
+            (-intensity).exp()
+        } else {
+            (mode * intensity.ln() - intensity - libm::lgamma(mode + 1.0)).exp()
+        };
+        let mut total = term(mode, mode_weight);
+        let mut covered = mode_weight;
+        let (mut up, mut up_weight) = (mode, mode_weight);
+        let (mut down, mut down_weight) = (mode, mode_weight);
+        while 1.0 - covered >= SERIES_TOLERANCE && (up_weight > 0.0 || down > 0.0) {
+            up += 1.0;
+            up_weight *= intensity / up;
+            total += term(up, up_weight);
+            covered += up_weight;
+            if down > 0.0 {
+                down_weight *= down / intensity;
+                down -= 1.0;
+                total += term(down, down_weight);
+                covered += down_weight;
+            }
+        }
+        total
+    }
+}
+
+/// The density of N(mean, var) at `y`. With zero variance, as for the
+/// no-jump term of a pure-jump model, it is a point mass: infinite at the
+/// mean and zero elsewhere.
+fn normal_density(y: f64, mean: f64, var: f64) -> f64 {
+    if var == 0.0 {
+        return if y == mean { f64::INFINITY } else { 0.0 };
+    }
+    let sd = var.sqrt();
+    normal::pdf((y - mean) / sd) / sd
+}
+
+/// The cumulative distribution function of N(mean, var) at `y`, a step at the
+/// mean with zero variance.
+fn normal_cdf(y: f64, mean: f64, var: f64) -> f64 {
+    if var == 0.0 {
+        return if y >= mean { 1.0 } else { 0.0 };
+    }
+    normal::cdf((y - mean) / var.sqrt())
+}
+
+impl StochasticProcess for MertonJumpDiffusion {
+    /// The drift of the diffusive part between jumps.
+    fn drift(&self, _t: f64, x: f64) -> f64 {
+        match self.dynamics {
+            JumpDynamics::Arithmetic => self.mu,
+            JumpDynamics::Geometric => (self.mu - self.lambda * self.jump_compensator()) * x,
+        }
+    }
+
+    fn diffusion(&self, _t: f64, x: f64) -> f64 {
+        match self.dynamics {
+            JumpDynamics::Arithmetic => self.sigma,
+            JumpDynamics::Geometric => self.sigma * x,
+        }
+    }
+
+    fn initial_value(&self) -> f64 {
+        self.s_0
+    }
+
+    fn time_horizon(&self) -> f64 {
+        self.t_end
+    }
+
+    fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        MertonJumpDiffusion::sample_path(self, rng)
+    }
+
+    fn simulate(&self) -> PathMatrix {
+        MertonJumpDiffusion::simulate(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mean_and_variance;
+
+    fn model(dynamics: JumpDynamics, n_paths: usize, n_steps: usize) -> MertonJumpDiffusion {
+        MertonJumpDiffusion::new(0.05, 0.2, 3.0, -0.1, 0.15, n_paths, n_steps, 1.0, 1.0)
+            .with_dynamics(dynamics)
+    }
+
+    #[test]
+    fn test_jump_diffusion_large_intensity() {
+        // exp(-lambda * t_end) underflows; with symmetric jumps S(1) is close
+        // to N(0, sigma^2 + lambda * jump_std^2).
+        let jd = MertonJumpDiffusion::new(0.0, 0.2, 1000.0, 0.0, 0.01, 1, 1, 1.0, 0.0);
+        let sd = (0.04_f64 + 1000.0 * 1e-4).sqrt();
+        assert!((jd.terminal_cdf(100.0) - 1.0).abs() < 1e-12);
+        assert!((jd.terminal_cdf(0.0) - 0.5).abs() < 1e-12);
+        assert!((jd.terminal_cdf(sd) - normal::cdf(1.0)).abs() < 1e-3);
+        let peak = 1.0 / (sd * (2.0 * std::f64::consts::PI).sqrt());
+        assert!((jd.terminal_density(0.0) - peak).abs() < 1e-3 * peak);
+    }
+
+    #[test]
+    fn test_pure_jump_distribution() {
+        // Without diffusion the no-jump term is a point mass at the drift point.
+        let mut merton = model(JumpDynamics::Arithmetic, 1, 1);
+        merton.sigma = 0.0;
+        let drift_point = merton.s_0 + merton.mu * merton.t_end;
+        let no_jump = (-merton.lambda * merton.t_end).exp();
+        assert!(merton.terminal_density(0.3) > 0.0);
+        assert!(merton.terminal_density(0.3).is_finite());
+        assert_eq!(merton.terminal_density(drift_point), f64::INFINITY);
+        let step = merton.terminal_cdf(drift_point) - merton.terminal_cdf(drift_point - 1e-9);
+        assert!((step - no_jump).abs() < 1e-6, "{step} vs {no_jump}");
+
+        let mut geometric = model(JumpDynamics::Geometric, 1, 1);
+        geometric.sigma = 0.0;
+        assert!(geometric.terminal_density(0.3).is_finite());
+        assert!(geometric.terminal_cdf(0.3).is_finite());
+    }
+
+    #[test]
+    fn test_jump_diffusion_simulation() {
+        let jd = model(JumpDynamics::Geometric, 50, 200).with_seed(1);
+        let paths = jd.simulate();
+        assert_eq!(paths.len(), 50);
+        assert_eq!(paths[0].len(), 201);
+        assert!(paths.iter().flatten().all(|&s| s > 0.0));
+    }
+
+    #[test]
+    fn test_zero_intensity_reduces_to_abm() {
+        let jd =
+            MertonJumpDiffusion::new(0.3, 0.8, 0.0, 1.0, 1.0, 20_000, 10, 2.0, 100.0).with_seed(2);
+        let (mean, var) = mean_and_variance(&jd.simulate().terminal_values());
+        let n = jd.n_paths as f64;
+        assert!((mean - 100.6).abs() < 5.0 * (1.28 / n).sqrt());
+        assert!((var - 1.28).abs() < 5.0 * 1.28 * (2.0 / (n - 1.0)).sqrt());
+    }
+
+    #[test]
+    fn test_jump_diffusion_terminal_moments() {
+        for dynamics in [JumpDynamics::Arithmetic, JumpDynamics::Geometric] {
+            let jd = model(dynamics, 40_000, 20).with_seed(3);
+            let (mean, var) = mean_and_variance(&jd.simulate().terminal_values());
+            let std_err = (jd.variance(1.0) / jd.n_paths as f64).sqrt();
+            assert!(
+                (mean - jd.mean(1.0)).abs() < 5.0 * std_err,
+                "{dynamics:?}: mean {mean} vs {}",
+                jd.mean(1.0)
+            );
+            assert!(
+                (var / jd.variance(1.0) - 1.0).abs() < 0.05,
+                "{dynamics:?}: variance {var} vs {}",
+                jd.variance(1.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_series_density_is_consistent() {
+        // The density integrates to one and its first moment matches the closed form.
+        for dynamics in [JumpDynamics::Arithmetic, JumpDynamics::Geometric] {
+            let jd = model(dynamics, 1, 1);
+            let (lo, hi, n) = (-4.0, 6.0, 20_000);
+            let h = (hi - lo) / n as f64;
+            let (mut mass, mut first, mut below_mean) = (0.0, 0.0, 0.0);
+            for i in 0..n {
+                let x = lo + (i as f64 + 0.5) * h;
+                let f = jd.terminal_density(x);
+                mass += f * h;
+                first += x * f * h;
+                if x < jd.mean(1.0) {
+                    below_mean += f * h;
+                }
+            }
+            assert!((mass - 1.0).abs() < 1e-6, "{dynamics:?}: mass {mass}");
+            assert!(
+                (first - jd.mean(1.0)).abs() < 1e-6,
+                "{dynamics:?}: mean {first}"
+            );
+            // The CDF agrees with the integrated density (up to the grid resolution).
+            assert!((jd.terminal_cdf(jd.mean(1.0)) - below_mean).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_terminal_distribution_matches_series() {
+        // Dvoretzky-Kiefer-Wolfowitz: P(sup |F_n - F| > eps) <= 2 * exp(-2 * n * eps^2).
+        for dynamics in [JumpDynamics::Arithmetic, JumpDynamics::Geometric] {
+            let jd = model(dynamics, 20_000, 10).with_seed(4);
+            let mut terminal = jd.simulate().terminal_values();
+            terminal.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            let n = terminal.len() as f64;
+            let ks = terminal
+                .iter()
+                .enumerate()
+                .map(|(i, &x)| {
+                    let f = jd.terminal_cdf(x);
+                    (f - i as f64 / n).abs().max((f - (i + 1) as f64 / n).abs())
+                })
+                .fold(0.0, f64::max);
+            let eps = ((2.0 / 1e-6_f64).ln() / (2.0 * n)).sqrt();
+            assert!(ks < eps, "{dynamics:?}: KS statistic {ks} exceeds {eps}");
+        }
+    }
+}
@@ -1,7 +1,95 @@
-/// A library for simulating stochastic processes.
-///
-/// This library currently includes the implementation of Arithmetic Brownian Motion (ABM).
-/// More stochastic processes can be added in future versions.
+//! A library for simulating stochastic processes.
+//!
+//! This library currently includes implementations of Arithmetic Brownian Motion (ABM),
+//! Geometric Brownian Motion (GBM), the Ornstein-Uhlenbeck (Vasicek) process, the
+//! Cox-Ingersoll-Ross (CIR) process and the Merton jump-diffusion, as well as the
+//! two-factor Heston stochastic volatility model and correlated multi-asset ABM.
+//! Other one-dimensional SDEs can be simulated from drift and diffusion closures
+//! with [`Sde`], and the [`convergence`] module measures the empirical error
+//! orders of a discretization. The [`stats`] module summarizes simulated paths
+//! online, per time step.
+//! More stochastic processes can be added in future versions.
+//!
+//! # Reproducibility
+//!
+//! Every process has an optional `seed`. `simulate` derives one ChaCha8 stream
+//! per path from it, so path `i` depends only on the seed and `i`; without a
+//! seed, a fresh one is drawn from the thread-local generator on every call.
+//!
+//! With the `parallel` cargo feature, `simulate` generates paths across threads
+//! using rayon. Since each path has its own deterministic random stream, seeded
+//! results are identical with or without the feature and for any thread count.
 
 pub mod abm;
+pub mod cir;
+pub mod coefficient;
+pub mod convergence;
+pub mod error;
+pub mod estimate;
+pub mod gbm;
+pub mod heston;
+pub mod jump;
+pub mod multi;
+pub mod ou;
+pub mod paths;
+pub mod qmc;
+pub mod scheme;
+pub mod sde;
+pub mod stats;
+pub mod stream;
+
+mod engine;
+mod linalg;
+mod normal;
+#[cfg(test)]
+mod testing;
+
+pub use abm::{AbmBuilder, ArithmeticBrownianMotion};
+pub use cir::{CirScheme, CoxIngersollRoss};
+pub use coefficient::Coefficient;
+pub use error::AbmError;
+pub use estimate::Estimate;
+pub use gbm::GeometricBrownianMotion;
+pub use heston::{Heston, HestonPaths};
+pub use jump::{JumpDynamics, MertonJumpDiffusion};
+pub use multi::MultiAssetAbm;
+pub use ou::OrnsteinUhlenbeck;
+pub use paths::PathMatrix;
+pub use qmc::{PathConstruction, Sobol};
+pub use scheme::Scheme;
+pub use sde::{Sde, SdeCoefficient};
+pub use stream::{PathIter, StepIter};
+
+use rand::Rng;
+
+/// A one-dimensional stochastic process driven by the SDE
+///
+/// dX = a(t, X) * dt + b(t, X) * dW
+///
+/// Where:
+/// - `a` is the drift coefficient
+/// - `b` is the diffusion coefficient
+/// - `dW` is a Wiener process increment
+///
+/// Pricing and risk code can be written against this trait so that it works
+/// with any process provided by the crate, as well as user-defined ones.
+pub trait StochasticProcess {
+    /// The drift coefficient `a(t, x)`.
+    fn drift(&self, t: f64, x: f64) -> f64;
+
+    /// The diffusion coefficient `b(t, x)`.
+    fn diffusion(&self, t: f64, x: f64) -> f64;
+
+    /// The value of the process at `t = 0`.
+    fn initial_value(&self) -> f64;
+
+    /// The end of the simulation horizon.
+    fn time_horizon(&self) -> f64;
+
+    /// Simulates a single path on the process' time grid, starting from the
+    /// initial value, drawing randomness from `rng`.
+    fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64>;
 
+    /// Simulates the full set of paths configured on the process.
+    fn simulate(&self) -> PathMatrix;
+}
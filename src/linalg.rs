@@ -0,0 +1,158 @@
+//! Small dense linear algebra on row-major square matrices.
+
+/// Relative size below which a Cholesky pivot counts as zero, so that singular
+/// matrices take the eigen-decomposition path instead of producing a factor
+/// dominated by rounding errors.
+const PIVOT_TOLERANCE: f64 = 1e-12;
+
+/// Maximum number of Jacobi sweeps before giving up on convergence.
+const JACOBI_MAX_SWEEPS: usize = 100;
+
+/// The lower-triangular Cholesky factor `L` of `a = L * L^T`, or `None` if `a`
+/// is not numerically positive definite.
+pub(crate) fn cholesky(a: &[f64], n: usize) -> Option<Vec<f64>> {
+    let mut l = vec![0.0; n * n];
+    for i in 0..n {
+        for j in 0..=i {
+            let dot: f64 = (0..j).map(|k| l[i * n + k] * l[j * n + k]).sum();
+            if i == j {
+                let pivot = a[i * n + i] - dot;
+                if pivot <= PIVOT_TOLERANCE * a[i * n + i] {
+                    return None;
+                }
+                l[i * n + i] = pivot.sqrt();
+            } else {
+                l[i * n + j] = (a[i * n + j] - dot) / l[j * n + j];
+            }
+        }
+    }
+    Some(l)
+}
+
+/// Eigenvalues and eigenvectors of the symmetric matrix `a` by cyclic Jacobi
+/// rotations. Column `k` of the returned row-major matrix is the eigenvector
+/// of the `k`-th eigenvalue.
+pub(crate) fn symmetric_eigen(a: &[f64], n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut a = a.to_vec();
+    let mut v = vec![0.0; n * n];
+    for i in 0..n {
+        v[i * n + i] = 1.0;
+    }
+    let scale: f64 = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+
+    for _ in 0..JACOBI_MAX_SWEEPS {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i * n + j].powi(2))
+            .sum::<f64>()
+            .sqrt();
+        if off <= f64::EPSILON * scale {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[p * n + q];
+                if apq == 0.0 {
+                    continue;
+                }
+                // Rotation angle that zeroes a[p][q].
+                let theta = (a[q * n + q] - a[p * n + p]) / (2.0 * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (akp, akq) = (a[k * n + p], a[k * n + q]);
+                    a[k * n + p] = c * akp - s * akq;
+                    a[k * n + q] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (a[p * n + k], a[q * n + k]);
+                    a[p * n + k] = c * apk - s * aqk;
+                    a[q * n + k] = s * apk + c * aqk;
+                }
+                for k in 0..n {
+                    let (vkp, vkq) = (v[k * n + p], v[k * n + q]);
+                    v[k * n + p] = c * vkp - s * vkq;
+                    v[k * n + q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i * n + i]).collect(), v)
+}
+
+/// A square root `B` of the symmetric positive semi-definite matrix `a`, with
+/// `a = B * B^T`: the Cholesky factor when `a` is positive definite, otherwise
+/// `V * sqrt(Lambda)` from the eigen-decomposition, which also handles
+/// singular matrices.
+///
+/// Returns `None` if `a` has an eigenvalue below `-tolerance`.
+pub(crate) fn psd_square_root(a: &[f64], n: usize, tolerance: f64) -> Option<Vec<f64>> {
+    if let Some(l) = cholesky(a, n) {
+        return Some(l);
+    }
+    let (values, vectors) = symmetric_eigen(a, n);
+    if values.iter().any(|&lambda| lambda < -tolerance) {
+        return None;
+    }
+    let roots: Vec<f64> = values
+        .iter()
+        .map(|&lambda| lambda.max(0.0).sqrt())
+        .collect();
+    Some(
+        (0..n * n)
+            .map(|idx| vectors[idx] * roots[idx % n])
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `b * b^T` for a row-major square matrix.
+    fn outer(b: &[f64], n: usize) -> Vec<f64> {
+        (0..n * n)
+            .map(|idx| {
+                let (i, j) = (idx / n, idx % n);
+                (0..n).map(|k| b[i * n + k] * b[j * n + k]).sum()
+            })
+            .collect()
+    }
+
+    fn assert_close(a: &[f64], b: &[f64], tol: f64) {
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).abs() < tol, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn test_cholesky_reconstructs() {
+        let a = [4.0, 2.0, 0.4, 2.0, 2.0, 0.5, 0.4, 0.5, 1.0];
+        let l = cholesky(&a, 3).unwrap();
+        assert_eq!((l[1], l[2], l[5]), (0.0, 0.0, 0.0));
+        assert_close(&outer(&l, 3), &a, 1e-14);
+        assert!(cholesky(&[1.0, 1.0, 1.0, 1.0], 2).is_none());
+    }
+
+    #[test]
+    fn test_symmetric_eigen() {
+        let a = [2.0, 1.0, 0.0, 1.0, 2.0, 1.0, 0.0, 1.0, 2.0];
+        let (mut values, _) = symmetric_eigen(&a, 3);
+        values.sort_by(f64::total_cmp);
+        let r = 2.0_f64.sqrt();
+        assert_close(&values, &[2.0 - r, 2.0, 2.0 + r], 1e-12);
+    }
+
+    #[test]
+    fn test_psd_square_root_of_singular_matrix() {
+        // Rank 2: the third variable is the normalized sum of the first two.
+        let r = 0.65_f64.sqrt();
+        let c = [1.0, 0.3, r, 0.3, 1.0, r, r, r, 1.0];
+        let root = psd_square_root(&c, 3, 1e-10).unwrap();
+        assert_close(&outer(&root, 3), &c, 1e-12);
+
+        let not_psd = [1.0, 0.9, -0.9, 0.9, 1.0, 0.9, -0.9, 0.9, 1.0];
+        assert!(psd_square_root(&not_psd, 3, 1e-10).is_none());
+    }
+}
@@ -0,0 +1,470 @@
+use crate::paths::uniform_grid;
+use crate::{engine, linalg, AbmError, PathMatrix};
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+/// Tolerance for the symmetry of the correlation matrix and for negative
+/// eigenvalues caused by rounding.
+const CORRELATION_TOLERANCE: f64 = 1e-10;
+
+/// Correlated Arithmetic Brownian Motions for a basket of assets:
+///
+/// dS_k = mu_k * dt + sigma_k * d_w_k,  with d_w_k * d_w_l = rho_kl * dt
+///
+/// Where:
+/// - `mu_k` is the drift of asset `k`
+/// - `sigma_k` is the volatility of asset `k`
+/// - `rho` is the correlation matrix of the Wiener processes `w_k`
+///
+/// The correlation matrix may be singular (e.g. perfectly correlated assets)
+/// as long as it is positive semi-definite.
+#[derive(Debug, Clone)]
+pub struct MultiAssetAbm {
+    pub mu: Vec<f64>,
+    pub sigma: Vec<f64>,
+    pub correlation: Vec<Vec<f64>>,
+    pub n_paths: usize,
+    pub n_steps: usize,
+    pub t_end: f64,
+    pub s_0: Vec<f64>,
+    /// Optional seed of the per-path random streams, see
+    /// [reproducibility](crate#reproducibility).
+    pub seed: Option<u64>,
+}
+
+impl MultiAssetAbm {
+    /// Creates a new multi-asset model.
+    ///
+    /// # Arguments
+    ///
+    /// * `mu` - The drift of each asset.
+    /// * `sigma` - The volatility of each asset.
+    /// * `correlation` - The correlation matrix of the driving Brownian motions.
+    /// * `n_paths` - Number of simulated scenarios.
+    /// * `n_steps` - Number of steps in each path.
+    /// * `t_end` - Total time of simulation.
+    /// * `s_0` - Initial value of each asset.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `MultiAssetAbm`.
+    pub fn new(
+        mu: Vec<f64>,
+        sigma: Vec<f64>,
+        correlation: Vec<Vec<f64>>,
+        n_paths: usize,
+        n_steps: usize,
+        t_end: f64,
+        s_0: Vec<f64>,
+    ) -> Self {
+        Self {
+            mu,
+            sigma,
+            correlation,
+            n_paths,
+            n_steps,
+            t_end,
+            s_0,
+            seed: None,
+        }
+    }
+
+    /// Like `new`, but validates the parameters.
+    ///
+    /// # Errors
+    ///
+    /// See `validate`.
+    pub fn try_new(
+        mu: Vec<f64>,
+        sigma: Vec<f64>,
+        correlation: Vec<Vec<f64>>,
+        n_paths: usize,
+        n_steps: usize,
+        t_end: f64,
+        s_0: Vec<f64>,
+    ) -> Result<Self, AbmError> {
+        let model = Self::new(mu, sigma, correlation, n_paths, n_steps, t_end, s_0);
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// Fixes the seed used by `simulate`, so that repeated runs produce
+    /// bit-identical paths.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Number of assets.
+    pub fn n_assets(&self) -> usize {
+        self.mu.len()
+    }
+
+    /// Checks that the current parameters describe a well-defined simulation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AbmError` if there are no assets, the vectors and the
+    /// correlation matrix do not all have one entry per asset, a parameter is
+    /// not finite, a volatility is negative, `n_steps` is zero, `t_end` is not
+    /// positive, or the correlation matrix is not a symmetric positive
+    /// semi-definite matrix with unit diagonal.
+    pub fn validate(&self) -> Result<(), AbmError> {
+        self.correlation_root().map(|_| ())
+    }
+
+    /// Validates the parameters and returns a row-major square root `B` of the
+    /// correlation matrix, `rho = B * B^T`.
+    fn correlation_root(&self) -> Result<Vec<f64>, AbmError> {
+        let n = self.n_assets();
+        if n == 0 {
+            return Err(AbmError::NoAssets);
+        }
+        for (name, len) in [
+            ("sigma", self.sigma.len()),
+            ("s_0", self.s_0.len()),
+            ("correlation", self.correlation.len()),
+        ] {
+            if len != n {
+                return Err(AbmError::DimensionMismatch {
+                    name,
+                    expected: n,
+                    got: len,
+                });
+            }
+        }
+        if let Some(row) = self.correlation.iter().find(|row| row.len() != n) {
+            return Err(AbmError::DimensionMismatch {
+                name: "correlation row",
+                expected: n,
+                got: row.len(),
+            });
+        }
+        let named =
+            |name, values: &[f64]| values.iter().map(move |&v| (name, v)).collect::<Vec<_>>();
+        for (name, value) in named("mu", &self.mu)
+            .into_iter()
+            .chain(named("sigma", &self.sigma))
+            .chain(named("s_0", &self.s_0))
+            .chain([("t_end", self.t_end)])
+        {
+            if !value.is_finite() {
+                return Err(AbmError::NonFinite { name, value });
+            }
+        }
+        if let Some(&sigma) = self.sigma.iter().find(|&&v| v < 0.0) {
+            return Err(AbmError::NegativeVolatility(sigma));
+        }
+        if self.n_steps == 0 {
+            return Err(AbmError::ZeroSteps);
+        }
+        if self.t_end <= 0.0 {
+            return Err(AbmError::NonPositiveHorizon(self.t_end));
+        }
+
+        let rho: Vec<f64> = self.correlation.concat();
+        for i in 0..n {
+            if rho[i * n + i] != 1.0 {
+                return Err(AbmError::InvalidCorrelation(format!(
+                    "diagonal entry {i} is {}, expected 1",
+                    rho[i * n + i]
+                )));
+            }
+            for j in 0..i {
+                let (a, b) = (rho[i * n + j], rho[j * n + i]);
+                if !a.is_finite() || !(-1.0..=1.0).contains(&a) {
+                    return Err(AbmError::InvalidCorrelation(format!(
+                        "entry ({i}, {j}) = {a} is not in [-1, 1]"
+                    )));
+                }
+                if (a - b).abs() > CORRELATION_TOLERANCE {
+                    return Err(AbmError::InvalidCorrelation(format!(
+                        "matrix is not symmetric at ({i}, {j}): {a} vs {b}"
+                    )));
+                }
+            }
+        }
+        linalg::psd_square_root(&rho, n, CORRELATION_TOLERANCE).ok_or_else(|| {
+            AbmError::InvalidCorrelation("matrix is not positive semi-definite".to_string())
+        })
+    }
+
+    /// Simulates the asset paths.
+    ///
+    /// # Returns
+    ///
+    /// One `PathMatrix` per asset, all on the same time grid, where row `i` of
+    /// every matrix belongs to the same scenario.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid; use `try_simulate` to get an error instead.
+    pub fn simulate(&self) -> Vec<PathMatrix> {
+        let root = self.expect_root();
+        let row_len = self.n_assets() * (self.n_steps + 1);
+        let mut data = vec![0.0; self.n_paths * row_len];
+        engine::fill_rows(
+            &mut data,
+            row_len,
+            engine::base_seed(self.seed),
+            |row, rng| self.fill_scenario(row, &root, rng),
+        );
+        self.split_assets(&data)
+    }
+
+    /// Like `simulate`, but reports invalid parameters as an error.
+    ///
+    /// # Errors
+    ///
+    /// See `validate`.
+    pub fn try_simulate(&self) -> Result<Vec<PathMatrix>, AbmError> {
+        self.validate()?;
+        Ok(self.simulate())
+    }
+
+    /// Simulates the asset paths using a caller-supplied random number generator.
+    ///
+    /// The `seed` field is ignored; the paths are fully determined by the state of `rng`.
+    pub fn simulate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<PathMatrix> {
+        let root = self.expect_root();
+        let row_len = self.n_assets() * (self.n_steps + 1);
+        let mut data = vec![0.0; self.n_paths * row_len];
+        for row in data.chunks_exact_mut(row_len) {
+            self.fill_scenario(row, &root, rng);
+        }
+        self.split_assets(&data)
+    }
+
+    /// The correlation root, panicking with a descriptive message if the
+    /// parameters are invalid.
+    fn expect_root(&self) -> Vec<f64> {
+        self.correlation_root()
+            .unwrap_or_else(|err| panic!("invalid MultiAssetAbm: {err}"))
+    }
+
+    /// Writes one scenario into `row`, which holds the path of each asset in turn.
+    fn fill_scenario<R: Rng + ?Sized>(&self, row: &mut [f64], root: &[f64], rng: &mut R) {
+        let n = self.n_assets();
+        let n_times = self.n_steps + 1;
+        let dt = self.t_end / self.n_steps as f64;
+        let sqrt_dt = dt.sqrt();
+        let mut z = vec![0.0; n];
+
+        for (k, &s_0) in self.s_0.iter().enumerate() {
+            row[k * n_times] = s_0;
+        }
+        for j in 1..n_times {
+            for z_k in z.iter_mut() {
+                *z_k = rng.sample(StandardNormal);
+            }
+            for k in 0..n {
+                let dw: f64 = (0..n).map(|l| root[k * n + l] * z[l]).sum::<f64>() * sqrt_dt;
+                let idx = k * n_times + j;
+                row[idx] = row[idx - 1] + self.mu[k] * dt + self.sigma[k] * dw;
+            }
+        }
+    }
+
+    /// Splits scenario rows into one path matrix per asset.
+    fn split_assets(&self, data: &[f64]) -> Vec<PathMatrix> {
+        let n_times = self.n_steps + 1;
+        let row_len = self.n_assets() * n_times;
+        (0..self.n_assets())
+            .map(|k| {
+                let values = data
+                    .chunks_exact(row_len)
+                    .flat_map(|row| &row[k * n_times..(k + 1) * n_times])
+                    .copied()
+                    .collect();
+                PathMatrix::from_vec(uniform_grid(self.n_steps, self.t_end), values)
+            })
+            .collect()
+    }
+
+    /// The analytic mean of each asset at time `t`: `s_0_k + mu_k * t`.
+    pub fn mean(&self, t: f64) -> Vec<f64> {
+        self.s_0
+            .iter()
+            .zip(&self.mu)
+            .map(|(s, m)| s + m * t)
+            .collect()
+    }
+
+    /// The analytic covariance matrix at time `t`: `rho_kl * sigma_k * sigma_l * t`.
+    pub fn covariance(&self, t: f64) -> Vec<Vec<f64>> {
+        self.correlation
+            .iter()
+            .zip(&self.sigma)
+            .map(|(row, s_k)| {
+                row.iter()
+                    .zip(&self.sigma)
+                    .map(|(rho, s_l)| rho * s_k * s_l * t)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mean_and_variance;
+
+    /// Sample correlation of the one-step increments of two assets.
+    fn increment_correlation(a: &PathMatrix, b: &PathMatrix) -> f64 {
+        let increments = |paths: &PathMatrix| -> Vec<f64> {
+            paths
+                .iter()
+                .flat_map(|p| p.windows(2).map(|w| w[1] - w[0]))
+                .collect()
+        };
+        let (x, y) = (increments(a), increments(b));
+        let n = x.len() as f64;
+        let (mx, my) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+        let cov: f64 = x.iter().zip(&y).map(|(u, v)| (u - mx) * (v - my)).sum();
+        let vx: f64 = x.iter().map(|u| (u - mx).powi(2)).sum();
+        let vy: f64 = y.iter().map(|v| (v - my).powi(2)).sum();
+        cov / (vx * vy).sqrt()
+    }
+
+    #[test]
+    fn test_multi_asset_shapes_and_seed() {
+        let rho = vec![vec![1.0, 0.5], vec![0.5, 1.0]];
+        let model = MultiAssetAbm::try_new(
+            vec![0.1, -0.2],
+            vec![0.3, 0.6],
+            rho,
+            10,
+            20,
+            1.0,
+            vec![1.0, 2.0],
+        )
+        .unwrap()
+        .with_seed(2);
+        let paths = model.simulate();
+        assert_eq!(paths.len(), 2);
+        assert_eq!((paths[1].n_paths(), paths[1].n_times()), (10, 21));
+        assert!(paths[0].iter().all(|p| p[0] == 1.0));
+        assert!(paths[1].iter().all(|p| p[0] == 2.0));
+        assert_eq!(paths, model.simulate());
+    }
+
+    #[test]
+    fn test_multi_asset_increment_correlations() {
+        let rho = vec![
+            vec![1.0, 0.7, -0.4],
+            vec![0.7, 1.0, 0.1],
+            vec![-0.4, 0.1, 1.0],
+        ];
+        let model = MultiAssetAbm::new(
+            vec![0.0; 3],
+            vec![0.2, 1.0, 3.0],
+            rho.clone(),
+            2_000,
+            50,
+            1.0,
+            vec![0.0; 3],
+        )
+        .with_seed(17);
+        let paths = model.simulate();
+        // 100_000 increment pairs: the standard error of a sample correlation
+        // is below (1 - rho^2) / sqrt(n) < 0.0032.
+        for k in 0..3 {
+            for l in 0..k {
+                let sample = increment_correlation(&paths[k], &paths[l]);
+                assert!((sample - rho[k][l]).abs() < 0.016, "({k}, {l}): {sample}");
+            }
+        }
+
+        let terminal = paths[1].terminal_values();
+        let n = terminal.len() as f64;
+        let (mean, _) = mean_and_variance(&terminal);
+        assert!(mean.abs() < 5.0 * (model.covariance(1.0)[1][1] / n).sqrt());
+    }
+
+    #[test]
+    fn test_multi_asset_singular_correlation() {
+        // The third asset is driven by the normalized sum of the first two.
+        let r = 0.65_f64.sqrt();
+        let rho = vec![vec![1.0, 0.3, r], vec![0.3, 1.0, r], vec![r, r, 1.0]];
+        let model = MultiAssetAbm::try_new(
+            vec![0.0; 3],
+            vec![1.0; 3],
+            rho,
+            1_000,
+            20,
+            1.0,
+            vec![0.0; 3],
+        )
+        .unwrap()
+        .with_seed(5);
+        let paths = model.simulate();
+        for (k, l, expected) in [(1, 0, 0.3), (2, 0, r), (2, 1, r)] {
+            let sample = increment_correlation(&paths[k], &paths[l]);
+            assert!((sample - expected).abs() < 0.02, "({k}, {l}): {sample}");
+        }
+
+        // Perfectly correlated assets move together.
+        let twins = MultiAssetAbm::try_new(
+            vec![0.0; 2],
+            vec![1.0; 2],
+            vec![vec![1.0, 1.0], vec![1.0, 1.0]],
+            5,
+            10,
+            1.0,
+            vec![0.0; 2],
+        )
+        .unwrap()
+        .with_seed(1)
+        .simulate();
+        for (a, b) in twins[0].as_slice().iter().zip(twins[1].as_slice()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_multi_asset_validation() {
+        let build = |rho: Vec<Vec<f64>>, sigma: Vec<f64>| {
+            MultiAssetAbm::try_new(vec![0.0; 3], sigma, rho, 1, 1, 1.0, vec![0.0; 3]).err()
+        };
+        let identity = vec![
+            vec![1.0, 0.0, 0.0],
+            vec![0.0, 1.0, 0.0],
+            vec![0.0, 0.0, 1.0],
+        ];
+        assert_eq!(
+            build(identity.clone(), vec![1.0; 2]),
+            Some(AbmError::DimensionMismatch {
+                name: "sigma",
+                expected: 3,
+                got: 2
+            })
+        );
+        assert!(build(identity, vec![1.0; 3]).is_none());
+
+        let not_psd = vec![
+            vec![1.0, 0.9, -0.9],
+            vec![0.9, 1.0, 0.9],
+            vec![-0.9, 0.9, 1.0],
+        ];
+        let asymmetric = vec![
+            vec![1.0, 0.5, 0.0],
+            vec![0.4, 1.0, 0.0],
+            vec![0.0, 0.0, 1.0],
+        ];
+        let bad_diagonal = vec![
+            vec![2.0, 0.0, 0.0],
+            vec![0.0, 1.0, 0.0],
+            vec![0.0, 0.0, 1.0],
+        ];
+        for rho in [not_psd, asymmetric, bad_diagonal] {
+            assert!(matches!(
+                build(rho, vec![1.0; 3]),
+                Some(AbmError::InvalidCorrelation(_))
+            ));
+        }
+
+        let empty = MultiAssetAbm::try_new(vec![], vec![], vec![], 3, 4, 1.0, vec![]);
+        assert_eq!(empty.err(), Some(AbmError::NoAssets));
+    }
+}
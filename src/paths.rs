@@ -0,0 +1,314 @@
+use crate::Estimate;
+use std::ops::{Index, IndexMut};
+
+/// Simulated paths stored in a single contiguous, row-major buffer.
+///
+/// Row `i` holds path `i`, column `j` holds the values at time `times()[j]`
+/// across all paths. Rows are exposed as slices, so `paths[i][j]` indexes the
+/// same way as the `Vec<Vec<f64>>` this type replaces.
+///
+/// A matrix can be flagged as holding antithetic pairs, rows `2k` and `2k + 1`
+/// being mirror images driven by the same draws, which [`PathMatrix::estimate`]
+/// takes into account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathMatrix {
+    data: Vec<f64>,
+    times: Vec<f64>,
+    n_paths: usize,
+    antithetic: bool,
+}
+
+impl PathMatrix {
+    /// Creates a matrix of `n_paths` paths on the given time grid, with every
+    /// value set to `initial`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `times` is empty.
+    pub fn new(times: Vec<f64>, n_paths: usize, initial: f64) -> Self {
+        assert!(!times.is_empty(), "time grid must not be empty");
+        Self {
+            data: vec![initial; n_paths * times.len()],
+            times,
+            n_paths,
+            antithetic: false,
+        }
+    }
+
+    /// Creates a matrix on the uniform grid `0, dt, ..., t_end` with `dt = t_end / n_steps`.
+    /// With `n_steps = 0` the grid is the single time `0`.
+    pub fn uniform(n_paths: usize, n_steps: usize, t_end: f64, initial: f64) -> Self {
+        Self::new(uniform_grid(n_steps, t_end), n_paths, initial)
+    }
+
+    /// Wraps an existing row-major buffer without copying it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data.len()` is not a multiple of `times.len()`.
+    pub fn from_vec(times: Vec<f64>, data: Vec<f64>) -> Self {
+        assert!(
+            !times.is_empty() && data.len().is_multiple_of(times.len()),
+            "buffer of length {} does not hold whole paths of length {}",
+            data.len(),
+            times.len()
+        );
+        let n_paths = data.len() / times.len();
+        Self {
+            data,
+            times,
+            n_paths,
+            antithetic: false,
+        }
+    }
+
+    /// Flags the rows as antithetic pairs (`2k`, `2k + 1`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `antithetic` is set on a matrix with an odd number of paths.
+    pub fn with_antithetic(mut self, antithetic: bool) -> Self {
+        assert!(
+            !antithetic || self.n_paths.is_multiple_of(2),
+            "antithetic pairs need an even number of paths, got {}",
+            self.n_paths
+        );
+        self.antithetic = antithetic;
+        self
+    }
+
+    /// Whether rows `2k` and `2k + 1` are antithetic pairs.
+    pub fn is_antithetic(&self) -> bool {
+        self.antithetic
+    }
+
+    /// Number of paths (rows).
+    pub fn n_paths(&self) -> usize {
+        self.n_paths
+    }
+
+    /// Number of time points per path (columns), i.e. `n_steps + 1`.
+    pub fn n_times(&self) -> usize {
+        self.times.len()
+    }
+
+    /// Number of paths, so that `paths.len()` keeps meaning what it did for `Vec<Vec<f64>>`.
+    pub fn len(&self) -> usize {
+        self.n_paths
+    }
+
+    /// Whether the matrix holds no paths.
+    pub fn is_empty(&self) -> bool {
+        self.n_paths == 0
+    }
+
+    /// The time grid shared by all paths.
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Path `i` as a slice of `n_times()` values.
+    pub fn path(&self, i: usize) -> &[f64] {
+        let n = self.n_times();
+        &self.data[i * n..(i + 1) * n]
+    }
+
+    /// Path `i` as a mutable slice.
+    pub fn path_mut(&mut self, i: usize) -> &mut [f64] {
+        let n = self.n_times();
+        &mut self.data[i * n..(i + 1) * n]
+    }
+
+    /// Iterates over the paths (rows).
+    pub fn iter(&self) -> std::slice::ChunksExact<'_, f64> {
+        self.data.chunks_exact(self.n_times())
+    }
+
+    /// Iterates mutably over the paths (rows).
+    pub fn iter_mut(&mut self) -> std::slice::ChunksExactMut<'_, f64> {
+        let n = self.n_times();
+        self.data.chunks_exact_mut(n)
+    }
+
+    /// The values of every path at time index `j` (column `j`).
+    pub fn column(&self, j: usize) -> impl ExactSizeIterator<Item = f64> + '_ {
+        assert!(j < self.n_times(), "time index {j} out of range");
+        self.data.iter().skip(j).step_by(self.n_times()).copied()
+    }
+
+    /// The values of every path at the end of the time grid.
+    pub fn terminal_values(&self) -> Vec<f64> {
+        self.column(self.n_times() - 1).collect()
+    }
+
+    /// The underlying row-major buffer.
+    pub fn as_slice(&self) -> &[f64] {
+        &self.data
+    }
+
+    /// The underlying row-major buffer, mutably.
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [f64] {
+        &mut self.data
+    }
+
+    /// Consumes the matrix and returns the underlying row-major buffer without copying.
+    pub fn into_vec(self) -> Vec<f64> {
+        self.data
+    }
+
+    /// The Monte Carlo estimate of `E[payoff(path)]` with its standard error.
+    ///
+    /// For antithetic matrices the payoffs of each pair are averaged first,
+    /// since the two paths of a pair are not independent; the standard error
+    /// then comes from the `n_paths / 2` independent pair averages.
+    pub fn estimate<F: Fn(&[f64]) -> f64>(&self, payoff: F) -> Estimate {
+        let payoffs: Vec<f64> = self.iter().map(payoff).collect();
+        if self.antithetic {
+            let pairs: Vec<f64> = payoffs
+                .chunks_exact(2)
+                .map(|p| 0.5 * (p[0] + p[1]))
+                .collect();
+            Estimate::from_samples(&pairs)
+        } else {
+            Estimate::from_samples(&payoffs)
+        }
+    }
+
+    /// Copies the paths into one vector per path.
+    pub fn to_vecs(&self) -> Vec<Vec<f64>> {
+        self.iter().map(<[f64]>::to_vec).collect()
+    }
+}
+
+/// The uniform grid `0, dt, ..., t_end` with `n_steps + 1` points, or `[0]`
+/// without steps.
+pub(crate) fn uniform_grid(n_steps: usize, t_end: f64) -> Vec<f64> {
+    if n_steps == 0 {
+        return vec![0.0];
+    }
+    (0..=n_steps)
+        .map(|j| t_end * j as f64 / n_steps as f64)
+        .collect()
+}
+
+impl Index<usize> for PathMatrix {
+    type Output = [f64];
+
+    fn index(&self, i: usize) -> &[f64] {
+        self.path(i)
+    }
+}
+
+impl IndexMut<usize> for PathMatrix {
+    fn index_mut(&mut self, i: usize) -> &mut [f64] {
+        self.path_mut(i)
+    }
+}
+
+impl Index<(usize, usize)> for PathMatrix {
+    type Output = f64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        assert!(j < self.n_times(), "time index {j} out of range");
+        &self.data[i * self.n_times() + j]
+    }
+}
+
+impl<'a> IntoIterator for &'a PathMatrix {
+    type Item = &'a [f64];
+    type IntoIter = std::slice::ChunksExact<'a, f64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Backwards-compatible conversion to the nested representation.
+///
+/// Each path needs its own allocation in a `Vec<Vec<f64>>`, so this copies the
+/// values; use [`PathMatrix::into_vec`] to take the buffer without copying.
+impl From<PathMatrix> for Vec<Vec<f64>> {
+    fn from(paths: PathMatrix) -> Self {
+        paths.to_vecs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> PathMatrix {
+        let mut paths = PathMatrix::uniform(3, 4, 2.0, 0.0);
+        for (i, path) in paths.iter_mut().enumerate() {
+            for (j, value) in path.iter_mut().enumerate() {
+                *value = (10 * i + j) as f64;
+            }
+        }
+        paths
+    }
+
+    #[test]
+    fn test_path_matrix_layout() {
+        let paths = sample();
+        assert_eq!(paths.len(), 3);
+        assert_eq!(paths.n_times(), 5);
+        assert_eq!(paths.times(), &[0.0, 0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(paths[1], [10.0, 11.0, 12.0, 13.0, 14.0]);
+        assert_eq!(paths[2][3], 23.0);
+        assert_eq!(paths[(2, 3)], 23.0);
+        assert_eq!(paths.as_slice()[5], 10.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "time index 5 out of range")]
+    fn test_path_matrix_index_checks_column() {
+        let _ = sample()[(0, 5)];
+    }
+
+    #[test]
+    fn test_path_matrix_without_steps() {
+        let paths = PathMatrix::uniform(2, 0, 1.0, 3.0);
+        assert_eq!(paths.times(), &[0.0]);
+        assert_eq!(paths.iter().count(), 2);
+        assert_eq!(paths.terminal_values(), vec![3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_path_matrix_columns() {
+        let paths = sample();
+        assert_eq!(paths.column(1).collect::<Vec<_>>(), vec![1.0, 11.0, 21.0]);
+        assert_eq!(paths.column(0).len(), 3);
+        assert_eq!(paths.terminal_values(), vec![4.0, 14.0, 24.0]);
+    }
+
+    #[test]
+    fn test_path_matrix_conversions() {
+        let paths = sample();
+        let nested: Vec<Vec<f64>> = paths.clone().into();
+        assert_eq!(nested.len(), 3);
+        assert_eq!(nested[1], paths[1].to_vec());
+
+        let times = paths.times().to_vec();
+        let buffer = paths.clone().into_vec();
+        let ptr = buffer.as_ptr();
+        let rebuilt = PathMatrix::from_vec(times, buffer);
+        assert_eq!(rebuilt, paths);
+        let buffer = rebuilt.into_vec();
+        assert_eq!(buffer.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_path_matrix_antithetic_estimate() {
+        // Terminal values 3, 13, 23, 33: pair averages 8 and 28.
+        let times = vec![0.0, 1.0];
+        let data = vec![0.0, 3.0, 0.0, 13.0, 0.0, 23.0, 0.0, 33.0];
+        let plain = PathMatrix::from_vec(times, data);
+        let terminal = |p: &[f64]| p[1];
+        assert_eq!(plain.estimate(terminal).n_samples, 4);
+
+        let paired = plain.with_antithetic(true);
+        let estimate = paired.estimate(terminal);
+        assert_eq!((estimate.mean, estimate.n_samples), (18.0, 2));
+        assert!((estimate.std_error - 10.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,610 @@
+//! Quasi-Monte Carlo building blocks: Sobol points, Owen scrambling and the
+//! construction of Brownian paths from normal variates.
+//!
+//! Low-discrepancy points converge faster than pseudo-random ones for smooth
+//! payoffs, provided the most important directions of the path are driven by
+//! the first coordinates, which the Brownian bridge and PCA constructions
+//! arrange. Scrambled points are randomized while keeping their
+//! equidistribution, so that independent scramblings give error estimates.
+
+use crate::linalg;
+use std::collections::VecDeque;
+
+pub use crate::normal::inverse_cdf as inverse_normal_cdf;
+
+/// Number of bits of each Sobol coordinate.
+const BITS: usize = 32;
+
+/// Number of dimensions covered by the Joe-Kuo direction numbers shipped with
+/// this crate, including the first (van der Corput) coordinate.
+pub const JOE_KUO_DIMENSIONS: usize = JOE_KUO_M.len() + 1;
+
+/// Initial direction numbers `m_1, ..., m_s` of dimensions 2 to 21, from the
+/// `new-joe-kuo-6.21201` table of Joe and Kuo (2008). Dimension `k + 2` uses
+/// the `k`-th primitive polynomial of [`primitive_polynomials`].
+const JOE_KUO_M: [&[u32]; 20] = [
+    &[1],
+    &[1, 3],
+    &[1, 3, 1],
+    &[1, 1, 1],
+    &[1, 1, 3, 3],
+    &[1, 3, 5, 13],
+    &[1, 1, 5, 5, 17],
+    &[1, 1, 5, 5, 5],
+    &[1, 1, 7, 11, 19],
+    &[1, 1, 5, 1, 1],
+    &[1, 1, 1, 3, 11],
+    &[1, 3, 5, 5, 31],
+    &[1, 3, 3, 9, 7, 49],
+    &[1, 1, 1, 15, 21, 21],
+    &[1, 3, 1, 13, 27, 49],
+    &[1, 1, 1, 15, 7, 5],
+    &[1, 3, 1, 15, 13, 25],
+    &[1, 1, 5, 5, 19, 61],
+    &[1, 3, 7, 11, 23, 15, 103],
+    &[1, 3, 7, 13, 13, 15, 69],
+];
+
+/// A Sobol low-discrepancy sequence in `[0, 1)^dimension`, optionally with
+/// hash-based Owen scrambling.
+///
+/// [`Sobol::new`] uses the Joe-Kuo direction numbers and is limited to their
+/// first [`JOE_KUO_DIMENSIONS`] dimensions. [`Sobol::extended`] lifts the
+/// limit by giving higher dimensions the next primitive polynomials with
+/// initial direction numbers drawn from a fixed hash; they remain valid Sobol
+/// coordinates but without the optimized two-dimensional projections.
+///
+/// Coordinates are returned at the centre of their `2^-32` cell, so they are
+/// never exactly 0 or 1 and map to finite normal variates.
+#[derive(Debug, Clone)]
+pub struct Sobol {
+    directions: Vec<[u32; BITS]>,
+    state: Vec<u32>,
+    index: u64,
+    scramble: Option<Vec<u32>>,
+}
+
+impl Sobol {
+    /// The unscrambled sequence with Joe-Kuo direction numbers, starting with
+    /// the point at the origin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dimension` is zero or exceeds [`JOE_KUO_DIMENSIONS`]; use
+    /// [`Sobol::extended`] to accept hashed direction numbers beyond it.
+    pub fn new(dimension: usize) -> Self {
+        assert!(
+            dimension <= JOE_KUO_DIMENSIONS,
+            "Sobol dimension {dimension} exceeds the {JOE_KUO_DIMENSIONS} Joe-Kuo dimensions; \
+             use Sobol::extended for hashed direction numbers"
+        );
+        Self::extended(dimension)
+    }
+
+    /// The unscrambled sequence in any number of dimensions, with hashed
+    /// initial direction numbers beyond [`JOE_KUO_DIMENSIONS`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dimension` is zero.
+    pub fn extended(dimension: usize) -> Self {
+        assert!(dimension > 0, "Sobol dimension must be at least 1");
+        Self {
+            directions: direction_numbers(dimension),
+            state: vec![0; dimension],
+            index: 0,
+            scramble: None,
+        }
+    }
+
+    /// The Joe-Kuo sequence with nested uniform (Owen) scrambling keyed by
+    /// `seed`, a shorthand for `Sobol::new(dimension).with_scrambling(seed)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Sobol::new`].
+    pub fn scrambled(dimension: usize, seed: u64) -> Self {
+        Self::new(dimension).with_scrambling(seed)
+    }
+
+    /// Applies nested uniform (Owen) scrambling keyed by `seed`, using the
+    /// hash-based permutation of Burley (2020). Every scrambled point is
+    /// uniformly distributed while the set keeps its stratification.
+    pub fn with_scrambling(mut self, seed: u64) -> Self {
+        self.scramble = Some(
+            (0..self.dimension() as u64)
+                .map(|k| (splitmix64(seed ^ splitmix64(k)) >> 32) as u32)
+                .collect(),
+        );
+        self
+    }
+
+    /// Number of coordinates of each point.
+    pub fn dimension(&self) -> usize {
+        self.state.len()
+    }
+
+    /// Writes the next point into `point`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `point` does not have `dimension()` entries or after `2^32`
+    /// points, when the sequence is exhausted.
+    pub fn next_point(&mut self, point: &mut [f64]) {
+        assert_eq!(
+            point.len(),
+            self.dimension(),
+            "point has the wrong dimension"
+        );
+        assert!(self.index < 1 << BITS, "Sobol sequence exhausted");
+        for (k, (u, &x)) in point.iter_mut().zip(&self.state).enumerate() {
+            let x = match &self.scramble {
+                Some(seeds) => owen_scramble(x, seeds[k]),
+                None => x,
+            };
+            *u = (x as f64 + 0.5) / (1u64 << BITS) as f64;
+        }
+        // Gray-code update: flip the direction number of the lowest zero bit.
+        let bit = self.index.trailing_ones() as usize;
+        if bit < BITS {
+            for (x, v) in self.state.iter_mut().zip(&self.directions) {
+                *x ^= v[bit];
+            }
+        }
+        self.index += 1;
+    }
+}
+
+impl Iterator for Sobol {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.index >= 1 << BITS {
+            return None;
+        }
+        let mut point = vec![0.0; self.dimension()];
+        self.next_point(&mut point);
+        Some(point)
+    }
+}
+
+/// Direction numbers `v_1, ..., v_32`, left-aligned in 32 bits, of the first
+/// `dimension` Sobol coordinates.
+fn direction_numbers(dimension: usize) -> Vec<[u32; BITS]> {
+    let mut directions = Vec::with_capacity(dimension);
+    // The first coordinate is the van der Corput sequence.
+    directions.push(std::array::from_fn(|i| 1u32 << (BITS - 1 - i)));
+
+    for (k, (degree, a)) in primitive_polynomials().take(dimension - 1).enumerate() {
+        let s = degree as usize;
+        let m: Vec<u32> = match JOE_KUO_M.get(k) {
+            Some(m) => m.to_vec(),
+            None => (1..=s)
+                .map(|i| {
+                    let hash = splitmix64(((k as u64) << 8) | i as u64) as u32;
+                    (hash | 1) & ((1 << i) - 1)
+                })
+                .collect(),
+        };
+        let mut v = [0u32; BITS];
+        for i in 0..s.min(BITS) {
+            v[i] = m[i] << (BITS - 1 - i);
+        }
+        for i in s..BITS {
+            v[i] = v[i - s] ^ (v[i - s] >> s);
+            for j in 1..s {
+                if (a >> (s - 1 - j)) & 1 == 1 {
+                    v[i] ^= v[i - j];
+                }
+            }
+        }
+        directions.push(v);
+    }
+    directions
+}
+
+/// The primitive polynomials over GF(2), ordered by degree and then by their
+/// middle coefficients, as `(degree, a)` where bit `degree - 1 - j` of `a` is
+/// the coefficient of `x^(degree - j)` for `j` in `1..degree`.
+pub(crate) fn primitive_polynomials() -> impl Iterator<Item = (u32, u64)> {
+    (1..=BITS as u32 - 1).flat_map(|degree| {
+        (0..1u64 << (degree - 1))
+            .filter(move |&a| is_primitive((1 << degree) | (a << 1) | 1, degree))
+            .map(move |a| (degree, a))
+    })
+}
+
+/// Whether `poly`, of the given degree, is primitive: `x` has multiplicative
+/// order exactly `2^degree - 1` modulo `poly`.
+fn is_primitive(poly: u64, degree: u32) -> bool {
+    let order = (1u64 << degree) - 1;
+    let x = reduce(0b10, poly, degree);
+    if pow_mod(x, order, poly, degree) != 1 {
+        return false;
+    }
+    prime_factors(order)
+        .into_iter()
+        .all(|q| pow_mod(x, order / q, poly, degree) != 1)
+}
+
+/// `a` reduced modulo `poly`.
+fn reduce(mut a: u64, poly: u64, degree: u32) -> u64 {
+    while a != 0 && 63 - a.leading_zeros() >= degree {
+        a ^= poly << (63 - a.leading_zeros() - degree);
+    }
+    a
+}
+
+/// `a * b` modulo `poly`, for `a` and `b` already reduced.
+fn mul_mod(a: u64, b: u64, poly: u64, degree: u32) -> u64 {
+    let mut result = 0;
+    let mut a = a;
+    let mut b = b;
+    while b != 0 {
+        if b & 1 == 1 {
+            result ^= a;
+        }
+        b >>= 1;
+        a = reduce(a << 1, poly, degree);
+    }
+    result
+}
+
+/// `a^e` modulo `poly`.
+fn pow_mod(a: u64, mut e: u64, poly: u64, degree: u32) -> u64 {
+    let mut result = reduce(1, poly, degree);
+    let mut base = a;
+    while e != 0 {
+        if e & 1 == 1 {
+            result = mul_mod(result, base, poly, degree);
+        }
+        base = mul_mod(base, base, poly, degree);
+        e >>= 1;
+    }
+    result
+}
+
+/// The distinct prime factors of `n`.
+fn prime_factors(mut n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            factors.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+/// Nested uniform scrambling of a 32-bit coordinate: a hash-based random
+/// permutation in which each bit only depends on the higher bits.
+fn owen_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras(x.reverse_bits(), seed).reverse_bits()
+}
+
+/// The Laine-Karras style hash of Burley (2020), whose bit `i` only depends on
+/// bits `0..=i` of the input.
+fn laine_karras(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50_b47c);
+    x ^= x.wrapping_mul(0xb82f_1e52);
+    x ^= x.wrapping_mul(0xc7af_e638);
+    x ^= x.wrapping_mul(0x8d22_f6e6);
+    x
+}
+
+/// The SplitMix64 finalizer, used to derive independent keys.
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// How a Brownian path on a time grid is built from independent standard
+/// normal variates, which for QMC decides which coordinates of a point matter
+/// most.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PathConstruction {
+    /// Step by step: variate `j` drives the increment over step `j`.
+    #[default]
+    Incremental,
+    /// Brownian bridge: the first variate sets the terminal value, the next
+    /// ones fill in midpoints by bisection.
+    BrownianBridge,
+    /// Principal components of the covariance `min(t_i, t_j)`, by decreasing
+    /// variance. Costs `O(n_steps^2)` per path.
+    Pca,
+}
+
+/// A precomputed mapping from normal variates to a Brownian path on a grid.
+#[derive(Debug, Clone)]
+pub(crate) enum BrownianBuilder {
+    Incremental {
+        sqrt_dt: Vec<f64>,
+    },
+    /// Per variate: the grid index it sets, its left and right neighbours
+    /// (`None` standing for `W(0) = 0` on the left, or no right end) and the
+    /// interpolation weights and conditional standard deviation.
+    Bridge {
+        steps: Vec<BridgeStep>,
+    },
+    Pca {
+        n: usize,
+        loadings: Vec<f64>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct BridgeStep {
+    target: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+    left_weight: f64,
+    right_weight: f64,
+    std_dev: f64,
+}
+
+impl BrownianBuilder {
+    /// Prepares the construction on `times`, which starts at 0.
+    pub(crate) fn new(construction: PathConstruction, times: &[f64]) -> Self {
+        let t = &times[1..];
+        let n = t.len();
+        match construction {
+            PathConstruction::Incremental => BrownianBuilder::Incremental {
+                sqrt_dt: times.windows(2).map(|w| (w[1] - w[0]).sqrt()).collect(),
+            },
+            PathConstruction::BrownianBridge => {
+                let time = |i: Option<usize>| i.map_or(0.0, |i| t[i]);
+                let mut steps = vec![BridgeStep {
+                    target: n - 1,
+                    left: None,
+                    right: None,
+                    left_weight: 0.0,
+                    right_weight: 0.0,
+                    std_dev: t[n - 1].sqrt(),
+                }];
+                // Intervals of unset indices `lo..hi` between known points.
+                let mut queue = VecDeque::from([(None, 0, n - 1)]);
+                while let Some((left, lo, hi)) = queue.pop_front() {
+                    if lo >= hi {
+                        continue;
+                    }
+                    let mid = lo + (hi - lo - 1) / 2;
+                    let (tl, tm, tr) = (time(left), t[mid], t[hi]);
+                    steps.push(BridgeStep {
+                        target: mid,
+                        left,
+                        right: Some(hi),
+                        left_weight: (tr - tm) / (tr - tl),
+                        right_weight: (tm - tl) / (tr - tl),
+                        std_dev: ((tm - tl) * (tr - tm) / (tr - tl)).sqrt(),
+                    });
+                    queue.push_back((left, lo, mid));
+                    queue.push_back((Some(mid), mid + 1, hi));
+                }
+                BrownianBuilder::Bridge { steps }
+            }
+            PathConstruction::Pca => {
+                let cov: Vec<f64> = (0..n * n).map(|idx| t[idx / n].min(t[idx % n])).collect();
+                let (values, vectors) = linalg::symmetric_eigen(&cov, n);
+                let mut order: Vec<usize> = (0..n).collect();
+                order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+                let loadings = (0..n * n)
+                    .map(|idx| {
+                        let (i, k) = (idx / n, order[idx % n]);
+                        vectors[i * n + k] * values[k].max(0.0).sqrt()
+                    })
+                    .collect();
+                BrownianBuilder::Pca { n, loadings }
+            }
+        }
+    }
+
+    /// Writes `W(t_1), ..., W(t_n)` built from the normal variates `z` into `w`.
+    pub(crate) fn build(&self, z: &[f64], w: &mut [f64]) {
+        match self {
+            BrownianBuilder::Incremental { sqrt_dt } => {
+                let mut level = 0.0;
+                for ((w, &z), &s) in w.iter_mut().zip(z).zip(sqrt_dt) {
+                    level += s * z;
+                    *w = level;
+                }
+            }
+            BrownianBuilder::Bridge { steps } => {
+                for (step, &z) in steps.iter().zip(z) {
+                    let left = step.left.map_or(0.0, |i| w[i]);
+                    let right = step.right.map_or(0.0, |i| w[i]);
+                    w[step.target] =
+                        step.left_weight * left + step.right_weight * right + step.std_dev * z;
+                }
+            }
+            BrownianBuilder::Pca { n, loadings } => {
+                for (w, row) in w.iter_mut().zip(loadings.chunks_exact(*n)) {
+                    *w = row.iter().zip(z).map(|(l, z)| l * z).sum();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primitive_polynomials_match_joe_kuo() {
+        let expected: [(u32, u64); 20] = [
+            (1, 0),
+            (2, 1),
+            (3, 1),
+            (3, 2),
+            (4, 1),
+            (4, 4),
+            (5, 2),
+            (5, 4),
+            (5, 7),
+            (5, 11),
+            (5, 13),
+            (5, 14),
+            (6, 1),
+            (6, 13),
+            (6, 16),
+            (6, 19),
+            (6, 22),
+            (6, 25),
+            (7, 1),
+            (7, 4),
+        ];
+        let found: Vec<_> = primitive_polynomials().take(20).collect();
+        assert_eq!(found, expected);
+        // There are phi(2^7 - 1) / 7 = 18 primitive polynomials of degree 7.
+        assert_eq!(
+            primitive_polynomials()
+                .take_while(|p| p.0 <= 7)
+                .filter(|p| p.0 == 7)
+                .count(),
+            18
+        );
+
+        for (m, &(degree, _)) in JOE_KUO_M.iter().zip(&expected) {
+            assert_eq!(m.len(), degree as usize);
+            for (i, &m_i) in m.iter().enumerate() {
+                assert!(m_i % 2 == 1 && m_i < 1 << (i + 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sobol_first_points() {
+        let points: Vec<Vec<f64>> = Sobol::new(3).take(5).collect();
+        let half_cell = 0.5 / 4_294_967_296.0;
+        let expected = [
+            [0.0, 0.0, 0.0],
+            [0.5, 0.5, 0.5],
+            [0.75, 0.25, 0.25],
+            [0.25, 0.75, 0.75],
+            [0.375, 0.375, 0.625],
+        ];
+        for (point, expected) in points.iter().zip(expected) {
+            for (u, e) in point.iter().zip(expected) {
+                assert_eq!(*u, e + half_cell);
+            }
+        }
+    }
+
+    /// Whether every box `[a / 2^k1, (a + 1) / 2^k1) x [b / 2^k2, ...)` with
+    /// `k1 + k2 = m` holds exactly one of the `2^m` points in dimensions `(i, j)`.
+    fn is_two_dimensional_net(points: &[Vec<f64>], i: usize, j: usize, m: u32) -> bool {
+        (0..=m).all(|k1| {
+            let k2 = m - k1;
+            let mut counts = vec![0; 1 << m];
+            for p in points {
+                let a = (p[i] * (1u64 << k1) as f64) as usize;
+                let b = (p[j] * (1u64 << k2) as f64) as usize;
+                counts[(a << k2) | b] += 1;
+            }
+            counts.iter().all(|&c| c == 1)
+        })
+    }
+
+    /// Whether each of the intervals `[a / 2^m, (a + 1) / 2^m)` holds exactly
+    /// one of the `2^m` points in dimension `k`.
+    fn is_stratified(points: &[Vec<f64>], k: usize, m: u32) -> bool {
+        let mut counts = vec![0; 1 << m];
+        for p in points {
+            counts[(p[k] * (1u64 << m) as f64) as usize] += 1;
+        }
+        counts.iter().all(|&c| c == 1)
+    }
+
+    #[test]
+    fn test_sobol_stratification() {
+        let m = 8;
+        let plain: Vec<Vec<f64>> = Sobol::extended(25).take(1 << m).collect();
+        let scrambled: Vec<Vec<f64>> = Sobol::extended(25)
+            .with_scrambling(9)
+            .take(1 << m)
+            .collect();
+        for points in [&plain, &scrambled] {
+            // Every coordinate, including generated ones, is stratified.
+            for k in 0..25 {
+                assert!(is_stratified(points, k, m));
+            }
+            assert!(is_two_dimensional_net(points, 0, 1, m));
+        }
+        assert_ne!(plain[1], scrambled[1]);
+        assert_ne!(
+            scrambled[1],
+            Sobol::extended(25).with_scrambling(10).nth(1).unwrap(),
+            "different seeds give different scramblings"
+        );
+        assert_eq!(
+            Sobol::scrambled(21, 9).nth(1).unwrap(),
+            Sobol::extended(21).with_scrambling(9).nth(1).unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the 21 Joe-Kuo dimensions")]
+    fn test_sobol_rejects_dimensions_beyond_table() {
+        let _ = Sobol::new(JOE_KUO_DIMENSIONS + 1);
+    }
+
+    /// The matrix `A` with `W = A * z`, probed with unit vectors.
+    fn construction_matrix(builder: &BrownianBuilder, n: usize) -> Vec<f64> {
+        let mut a = vec![0.0; n * n];
+        let mut w = vec![0.0; n];
+        for k in 0..n {
+            let mut z = vec![0.0; n];
+            z[k] = 1.0;
+            builder.build(&z, &mut w);
+            for i in 0..n {
+                a[i * n + k] = w[i];
+            }
+        }
+        a
+    }
+
+    #[test]
+    fn test_constructions_have_brownian_covariance() {
+        let times = [0.0, 0.1, 0.25, 0.3, 0.7, 0.75, 1.0, 1.6];
+        let n = times.len() - 1;
+        for construction in [
+            PathConstruction::Incremental,
+            PathConstruction::BrownianBridge,
+            PathConstruction::Pca,
+        ] {
+            let a = construction_matrix(&BrownianBuilder::new(construction, &times), n);
+            for i in 0..n {
+                for j in 0..n {
+                    let cov: f64 = (0..n).map(|k| a[i * n + k] * a[j * n + k]).sum();
+                    let expected = times[i + 1].min(times[j + 1]);
+                    assert!(
+                        (cov - expected).abs() < 1e-12,
+                        "{construction:?} ({i}, {j})"
+                    );
+                }
+            }
+        }
+
+        // The bridge sets the terminal value from the first variate alone,
+        // and PCA puts the most variance in the first component.
+        let bridge = construction_matrix(
+            &BrownianBuilder::new(PathConstruction::BrownianBridge, &times),
+            n,
+        );
+        assert!((bridge[(n - 1) * n] - 1.6_f64.sqrt()).abs() < 1e-15);
+        assert!((1..n).all(|k| bridge[(n - 1) * n + k] == 0.0));
+        let pca = construction_matrix(&BrownianBuilder::new(PathConstruction::Pca, &times), n);
+        let column_variance = |k: usize| (0..n).map(|i| pca[i * n + k].powi(2)).sum::<f64>();
+        assert!((1..n).all(|k| column_variance(k - 1) >= column_variance(k)));
+    }
+}
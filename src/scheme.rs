@@ -0,0 +1,18 @@
+/// Time-stepping scheme used to advance a process between grid points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Scheme {
+    /// Sample the exact transition distribution of the process over each step.
+    #[default]
+    Exact,
+    /// The Euler-Maruyama scheme `X + a(t, X) * dt + b(t, X) * d_w`, with the
+    /// coefficients frozen at the start of each step.
+    EulerMaruyama,
+    /// The Milstein scheme, which adds `b * b' * (d_w^2 - dt) / 2` to the Euler
+    /// step, `b'` being the derivative of the diffusion in the state. Strong
+    /// order 1 instead of 1/2 when the diffusion depends on the state.
+    Milstein,
+    /// Platen's derivative-free stochastic Runge-Kutta scheme of strong order 1,
+    /// which replaces `b * b'` in the Milstein correction by a finite difference
+    /// of `b` at the support value `X + a * dt + b * sqrt(dt)`.
+    RungeKutta,
+}
@@ -0,0 +1,433 @@
+use crate::convergence::Discretization;
+use crate::paths::uniform_grid;
+use crate::{engine, AbmError, PathMatrix, Scheme, StochasticProcess};
+use rand::Rng;
+use rand_distr::StandardNormal;
+use std::fmt;
+use std::sync::Arc;
+
+/// Relative step of the central difference approximating the diffusion derivative.
+const DERIVATIVE_STEP: f64 = 1e-6;
+
+/// A coefficient `f(t, x)` of a general SDE.
+pub type SdeCoefficient = Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>;
+
+/// A general one-dimensional SDE with user-defined coefficients:
+///
+/// dX = a(t, X) * dt + b(t, X) * d_w
+///
+/// Where:
+/// - `a` is the drift closure
+/// - `b` is the diffusion closure
+/// - `d_w` is a Wiener process increment, distributed as N(0, dt)
+///
+/// Useful for prototyping models that do not have a dedicated type. Paths are
+/// generated by discretization, so only approximating schemes are available:
+/// Euler-Maruyama, Milstein and stochastic Runge-Kutta. `Scheme::Exact` is rejected.
+///
+/// # Example
+///
+/// ```
+/// use stochastic_abm::Sde;
+///
+/// // A mean-reverting square-root process.
+/// let sde = Sde::new(
+///     |_t, x| 2.0 * (0.04 - x),
+///     |_t, x: f64| 0.3 * x.max(0.0).sqrt(),
+///     0.04,
+///     100,
+///     250,
+///     1.0,
+/// )
+/// .with_seed(7);
+/// let paths = sde.simulate();
+/// assert_eq!(paths.n_times(), 251);
+/// ```
+#[derive(Clone)]
+pub struct Sde {
+    pub drift: SdeCoefficient,
+    pub diffusion: SdeCoefficient,
+    pub x_0: f64,
+    pub n_paths: usize,
+    pub n_steps: usize,
+    pub t_end: f64,
+    /// Optional seed of the per-path random streams, see
+    /// [reproducibility](crate#reproducibility).
+    pub seed: Option<u64>,
+    /// The time-stepping scheme, `Scheme::EulerMaruyama` by default.
+    pub scheme: Scheme,
+    /// The derivative `b'(t, x)` of the diffusion in `x`, used by
+    /// `Scheme::Milstein`. When `None` it is approximated by a central difference.
+    pub diffusion_derivative: Option<SdeCoefficient>,
+}
+
+impl Sde {
+    /// Creates a new SDE from its drift and diffusion closures.
+    ///
+    /// # Arguments
+    ///
+    /// * `drift` - The drift `a(t, x)`.
+    /// * `diffusion` - The diffusion `b(t, x)`.
+    /// * `x_0` - Initial value of the process.
+    /// * `n_paths` - Number of simulated paths.
+    /// * `n_steps` - Number of steps in each path.
+    /// * `t_end` - Total time of simulation.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `Sde`.
+    pub fn new<A, B>(
+        drift: A,
+        diffusion: B,
+        x_0: f64,
+        n_paths: usize,
+        n_steps: usize,
+        t_end: f64,
+    ) -> Self
+    where
+        A: Fn(f64, f64) -> f64 + Send + Sync + 'static,
+        B: Fn(f64, f64) -> f64 + Send + Sync + 'static,
+    {
+        Self {
+            drift: Arc::new(drift),
+            diffusion: Arc::new(diffusion),
+            x_0,
+            n_paths,
+            n_steps,
+            t_end,
+            seed: None,
+            scheme: Scheme::EulerMaruyama,
+            diffusion_derivative: None,
+        }
+    }
+
+    /// Fixes the seed used by `simulate`, so that repeated runs produce
+    /// bit-identical paths.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Selects the time-stepping scheme.
+    pub fn with_scheme(mut self, scheme: Scheme) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
+    /// Supplies the analytic derivative `b'(t, x)` of the diffusion for the
+    /// Milstein scheme, instead of a numerical one.
+    pub fn with_diffusion_derivative<D>(mut self, derivative: D) -> Self
+    where
+        D: Fn(f64, f64) -> f64 + Send + Sync + 'static,
+    {
+        self.diffusion_derivative = Some(Arc::new(derivative));
+        self
+    }
+
+    /// Checks that the current parameters describe a well-defined simulation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AbmError` if `x_0` or `t_end` is not finite, `n_steps` is
+    /// zero, `t_end` is not positive, or the scheme needs a closed-form
+    /// transition (`Scheme::Exact`).
+    pub fn validate(&self) -> Result<(), AbmError> {
+        for (name, value) in [("x_0", self.x_0), ("t_end", self.t_end)] {
+            if !value.is_finite() {
+                return Err(AbmError::NonFinite { name, value });
+            }
+        }
+        if self.n_steps == 0 {
+            return Err(AbmError::ZeroSteps);
+        }
+        if self.t_end <= 0.0 {
+            return Err(AbmError::NonPositiveHorizon(self.t_end));
+        }
+        if self.scheme == Scheme::Exact {
+            return Err(AbmError::UnsupportedScheme(self.scheme));
+        }
+        Ok(())
+    }
+
+    /// Panics with a descriptive message if the parameters are invalid.
+    fn assert_valid(&self) {
+        if let Err(err) = self.validate() {
+            panic!("invalid Sde: {err}");
+        }
+    }
+
+    /// Simulates paths of the SDE with the configured scheme.
+    ///
+    /// # Returns
+    ///
+    /// A `PathMatrix` whose rows are the simulated paths.
+    ///
+    /// Each path has `n_steps + 1` values, including the initial value `x_0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid; use `try_simulate` to get an error instead.
+    pub fn simulate(&self) -> PathMatrix {
+        self.assert_valid();
+        let mut paths = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.x_0);
+        let times = paths.times().to_vec();
+        engine::fill_paths(&mut paths, engine::base_seed(self.seed), |path, rng| {
+            self.fill_path(path, &times, rng)
+        });
+        paths
+    }
+
+    /// Like `simulate`, but reports invalid parameters as an error.
+    ///
+    /// # Errors
+    ///
+    /// See `validate`.
+    pub fn try_simulate(&self) -> Result<PathMatrix, AbmError> {
+        self.validate()?;
+        Ok(self.simulate())
+    }
+
+    /// Simulates paths using a caller-supplied random number generator.
+    ///
+    /// The `seed` field is ignored; the paths are fully determined by the state of `rng`.
+    pub fn simulate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> PathMatrix {
+        self.assert_valid();
+        let mut paths = PathMatrix::uniform(self.n_paths, self.n_steps, self.t_end, self.x_0);
+        let times = paths.times().to_vec();
+        for path in paths.iter_mut() {
+            self.fill_path(path, &times, rng);
+        }
+        paths
+    }
+
+    /// Simulates a single path of `n_steps + 1` values.
+    pub fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        self.assert_valid();
+        let times = uniform_grid(self.n_steps, self.t_end);
+        let mut path = vec![self.x_0; times.len()];
+        self.fill_path(&mut path, &times, rng);
+        path
+    }
+
+    /// Advances the state `x` at time `t` over `dt` given the Brownian increment `dw`.
+    fn step(&self, t: f64, x: f64, dt: f64, dw: f64) -> f64 {
+        let (a, b) = ((self.drift)(t, x), (self.diffusion)(t, x));
+        let euler = x + a * dt + b * dw;
+        match self.scheme {
+            Scheme::EulerMaruyama => euler,
+            Scheme::Milstein => euler + 0.5 * b * self.diffusion_slope(t, x) * (dw * dw - dt),
+            Scheme::RungeKutta => {
+                let sqrt_dt = dt.sqrt();
+                let support = x + a * dt + b * sqrt_dt;
+                let b_support = (self.diffusion)(t, support);
+                euler + (b_support - b) * (dw * dw - dt) / (2.0 * sqrt_dt)
+            }
+            Scheme::Exact => panic!("Sde cannot be simulated with Scheme::Exact"),
+        }
+    }
+
+    /// `b'(t, x)`, from the user's closure or a central difference.
+    fn diffusion_slope(&self, t: f64, x: f64) -> f64 {
+        match &self.diffusion_derivative {
+            Some(derivative) => derivative(t, x),
+            None => {
+                let h = DERIVATIVE_STEP * x.abs().max(1.0);
+                ((self.diffusion)(t, x + h) - (self.diffusion)(t, x - h)) / (2.0 * h)
+            }
+        }
+    }
+
+    /// Writes one path observed at `times` into `path`.
+    fn fill_path<R: Rng + ?Sized>(&self, path: &mut [f64], times: &[f64], rng: &mut R) {
+        path[0] = self.x_0;
+        for (j, w) in times.windows(2).enumerate() {
+            let dt = w[1] - w[0];
+            let z: f64 = rng.sample(StandardNormal);
+            path[j + 1] = self.step(w[0], path[j], dt, dt.sqrt() * z);
+        }
+    }
+}
+
+impl fmt::Debug for Sde {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sde")
+            .field("x_0", &self.x_0)
+            .field("n_paths", &self.n_paths)
+            .field("n_steps", &self.n_steps)
+            .field("t_end", &self.t_end)
+            .field("seed", &self.seed)
+            .field("scheme", &self.scheme)
+            .field(
+                "diffusion_derivative",
+                &self.diffusion_derivative.as_ref().map(|_| ".."),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl StochasticProcess for Sde {
+    fn drift(&self, t: f64, x: f64) -> f64 {
+        (self.drift)(t, x)
+    }
+
+    fn diffusion(&self, t: f64, x: f64) -> f64 {
+        (self.diffusion)(t, x)
+    }
+
+    fn initial_value(&self) -> f64 {
+        self.x_0
+    }
+
+    fn time_horizon(&self) -> f64 {
+        self.t_end
+    }
+
+    fn sample_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        Sde::sample_path(self, rng)
+    }
+
+    fn simulate(&self) -> PathMatrix {
+        Sde::simulate(self)
+    }
+}
+
+impl Discretization for Sde {
+    fn step(&self, t: f64, x: f64, dt: f64, dw: f64) -> f64 {
+        Sde::step(self, t, x, dt, dw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mean_and_variance;
+    use crate::{ArithmeticBrownianMotion, OrnsteinUhlenbeck};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_sde_matches_abm_euler() {
+        // With constant coefficients the Euler loop reproduces the ABM paths
+        // drawn from the same streams.
+        let abm = ArithmeticBrownianMotion::new(0.3, 0.7, 20, 50, 2.0, 1.0)
+            .with_scheme(Scheme::EulerMaruyama)
+            .with_seed(4);
+        let sde = Sde::new(|_, _| 0.3, |_, _| 0.7, 1.0, 20, 50, 2.0).with_seed(4);
+        let (expected, paths) = (abm.simulate(), sde.simulate());
+        assert_eq!(paths.times(), expected.times());
+        for (a, b) in paths.as_slice().iter().zip(expected.as_slice()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let first = sde.simulate_with_rng(&mut rng);
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        assert_eq!(first, sde.simulate_with_rng(&mut rng));
+    }
+
+    #[test]
+    fn test_sde_ou_moments() {
+        let (theta, mu, sigma, x_0, t_end) = (1.5, 0.5, 0.4, 2.0, 1.0);
+        let n_paths = 20_000;
+        let sde = Sde::new(
+            move |_, x| theta * (mu - x),
+            move |_, _| sigma,
+            x_0,
+            n_paths,
+            500,
+            t_end,
+        )
+        .with_seed(13);
+        let (mean, var) = mean_and_variance(&sde.simulate().terminal_values());
+
+        let ou = OrnsteinUhlenbeck::new(theta, mu, sigma, 1, 1, t_end, x_0);
+        let n = n_paths as f64;
+        let expected_var = ou.variance(t_end);
+        // Discretization bias at 500 steps is far below the sampling error.
+        assert!((mean - ou.mean(t_end)).abs() < 5.0 * (expected_var / n).sqrt());
+        assert!((var - expected_var).abs() < 5.0 * expected_var * (2.0 / (n - 1.0)).sqrt());
+    }
+
+    /// Mean absolute error at `t_end` against the exact GBM solution driven by
+    /// the same Brownian increments.
+    fn gbm_strong_error(scheme: Scheme, n_steps: usize) -> f64 {
+        let (mu, sigma, x_0, t_end, n_paths) = (0.05, 0.5, 1.0, 1.0, 2_000);
+        let sde = Sde::new(
+            move |_, x| mu * x,
+            move |_, x| sigma * x,
+            x_0,
+            1,
+            n_steps,
+            t_end,
+        )
+        .with_scheme(scheme);
+        let sqrt_dt = (t_end / n_steps as f64).sqrt();
+        let mut rng = ChaCha8Rng::seed_from_u64(21);
+        let mut total = 0.0;
+        for _ in 0..n_paths {
+            // Every scheme draws one normal per step, so a clone of the
+            // generator replays the Brownian path.
+            let mut replay = rng.clone();
+            let w: f64 = (0..n_steps)
+                .map(|_| sqrt_dt * replay.sample::<f64, _>(StandardNormal))
+                .sum();
+            let exact = x_0 * ((mu - 0.5 * sigma * sigma) * t_end + sigma * w).exp();
+            let path = sde.sample_path(&mut rng);
+            total += (path[n_steps] - exact).abs();
+        }
+        total / n_paths as f64
+    }
+
+    fn gbm_strong_order(scheme: Scheme) -> f64 {
+        let (coarse, fine) = (gbm_strong_error(scheme, 8), gbm_strong_error(scheme, 128));
+        (coarse / fine).ln() / 16.0_f64.ln()
+    }
+
+    #[test]
+    fn test_sde_strong_convergence_on_gbm() {
+        let euler = gbm_strong_order(Scheme::EulerMaruyama);
+        let milstein = gbm_strong_order(Scheme::Milstein);
+        let runge_kutta = gbm_strong_order(Scheme::RungeKutta);
+        assert!((euler - 0.5).abs() < 0.15, "Euler order {euler}");
+        assert!((milstein - 1.0).abs() < 0.15, "Milstein order {milstein}");
+        assert!(
+            (runge_kutta - 1.0).abs() < 0.15,
+            "Runge-Kutta order {runge_kutta}"
+        );
+        assert!(
+            gbm_strong_error(Scheme::Milstein, 128) < gbm_strong_error(Scheme::EulerMaruyama, 128)
+        );
+    }
+
+    #[test]
+    fn test_sde_milstein_numerical_derivative() {
+        let sde = Sde::new(|_, x| 0.1 * x, |_, x: f64| 0.3 * x.sin(), 1.0, 50, 100, 1.0)
+            .with_scheme(Scheme::Milstein)
+            .with_seed(3);
+        let analytic = sde
+            .clone()
+            .with_diffusion_derivative(|_, x: f64| 0.3 * x.cos());
+        for (a, b) in sde
+            .simulate()
+            .as_slice()
+            .iter()
+            .zip(analytic.simulate().as_slice())
+        {
+            assert!((a - b).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_sde_rejects_exact_scheme() {
+        let sde = Sde::new(|_, x| x, |_, _| 1.0, 0.0, 1, 10, 1.0).with_scheme(Scheme::Exact);
+        assert_eq!(
+            sde.try_simulate(),
+            Err(AbmError::UnsupportedScheme(Scheme::Exact))
+        );
+        let sde = Sde::new(|_, x| x, |_, _| 1.0, f64::NAN, 1, 10, 1.0);
+        assert!(matches!(
+            sde.validate(),
+            Err(AbmError::NonFinite { name: "x_0", .. })
+        ));
+    }
+}
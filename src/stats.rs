@@ -0,0 +1,789 @@
+//! Online summary statistics over simulated paths.
+//!
+//! Every accumulator consumes values one at a time in constant or bounded
+//! memory, so statistics can be collected from a [`PathMatrix`] as well as
+//! from the streaming iterators [`PathIter`](crate::PathIter) and
+//! [`StepIter`](crate::StepIter) without materializing the paths:
+//!
+//! ```
+//! use stochastic_abm::stats::PathStatistics;
+//! use stochastic_abm::ArithmeticBrownianMotion;
+//!
+//! let abm = ArithmeticBrownianMotion::new(0.5, 1.0, 10_000, 50, 1.0, 0.0).with_seed(3);
+//! let mut stats = PathStatistics::new(&abm.time_points());
+//! for path in abm.paths() {
+//!     stats.push_path(&path);
+//! }
+//! let terminal = stats.terminal();
+//! let (lo, hi) = terminal.confidence_interval();
+//! assert!(lo < 0.5 && 0.5 < hi);
+//! assert!((terminal.quantile(0.5) - 0.5).abs() < 0.05);
+//! ```
+
+use crate::{Estimate, PathMatrix};
+use std::f64::consts::PI;
+
+/// Default t-digest compression, trading roughly 1% relative quantile error in
+/// the body of the distribution for a few hundred centroids.
+const DEFAULT_COMPRESSION: f64 = 100.0;
+
+/// Mean, variance and range of a stream of values, updated with Welford's
+/// algorithm, which avoids the cancellation of the naive sum-of-squares.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunningStats {
+    n: usize,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for RunningStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunningStats {
+    /// An empty accumulator.
+    pub fn new() -> Self {
+        Self {
+            n: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Adds one value.
+    pub fn push(&mut self, x: f64) {
+        self.n += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    /// Combines with the statistics of a disjoint set of values (Chan et al.),
+    /// as if all of them had been pushed into `self`.
+    pub fn merge(&mut self, other: &RunningStats) {
+        if other.n == 0 {
+            return;
+        }
+        let n = (self.n + other.n) as f64;
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.n as f64 / n;
+        self.m2 += other.m2 + delta * delta * self.n as f64 * other.n as f64 / n;
+        self.n += other.n;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// The number of values pushed.
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Whether no value has been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// The sample mean, NaN if empty.
+    pub fn mean(&self) -> f64 {
+        if self.n == 0 {
+            f64::NAN
+        } else {
+            self.mean
+        }
+    }
+
+    /// The unbiased sample variance, NaN for fewer than two values.
+    pub fn variance(&self) -> f64 {
+        if self.n < 2 {
+            f64::NAN
+        } else {
+            self.m2 / (self.n - 1) as f64
+        }
+    }
+
+    /// The sample standard deviation.
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// The smallest value, `+inf` if empty.
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// The largest value, `-inf` if empty.
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// The mean as a Monte Carlo estimate, treating the values as independent.
+    pub fn estimate(&self) -> Estimate {
+        Estimate {
+            mean: self.mean(),
+            std_error: (self.variance() / self.n as f64).sqrt(),
+            n_samples: self.n,
+        }
+    }
+}
+
+impl Extend<f64> for RunningStats {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl FromIterator<f64> for RunningStats {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut stats = Self::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+/// A single quantile tracked with the P² algorithm (Jain and Chlamtac, 1985),
+/// which keeps five markers and adjusts their heights by piecewise-parabolic
+/// interpolation.
+///
+/// Uses constant memory; the estimate is exact for fewer than five values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct P2Quantile {
+    p: f64,
+    n: usize,
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+    rates: [f64; 5],
+}
+
+impl P2Quantile {
+    /// A tracker for the `p`-quantile.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in `(0, 1)`.
+    pub fn new(p: f64) -> Self {
+        assert!(p > 0.0 && p < 1.0, "quantile level {p} is not in (0, 1)");
+        Self {
+            p,
+            n: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            rates: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    /// The tracked quantile level.
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+
+    /// The number of values pushed.
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Whether no value has been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Adds one value.
+    pub fn push(&mut self, x: f64) {
+        if self.n < 5 {
+            self.heights[self.n] = x;
+            self.n += 1;
+            if self.n == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.n += 1;
+
+        let h = &mut self.heights;
+        let k = if x < h[0] {
+            h[0] = x;
+            0
+        } else if x >= h[4] {
+            h[4] = x;
+            3
+        } else {
+            (0..4).find(|&i| x < h[i + 1]).unwrap_or(3)
+        };
+        for position in &mut self.positions[k + 1..] {
+            *position += 1.0;
+        }
+        for (desired, rate) in self.desired.iter_mut().zip(&self.rates) {
+            *desired += rate;
+        }
+
+        for i in 1..4 {
+            let d = self.desired[i] - self.positions[i];
+            let (below, above) = (
+                self.positions[i - 1] - self.positions[i],
+                self.positions[i + 1] - self.positions[i],
+            );
+            if (d >= 1.0 && above > 1.0) || (d <= -1.0 && below < -1.0) {
+                let d = d.signum();
+                let parabolic = self.parabolic(i, d);
+                self.heights[i] =
+                    if self.heights[i - 1] < parabolic && parabolic < self.heights[i + 1] {
+                        parabolic
+                    } else {
+                        self.linear(i, d)
+                    };
+                self.positions[i] += d;
+            }
+        }
+    }
+
+    /// The current estimate of the quantile, NaN if empty.
+    pub fn quantile(&self) -> f64 {
+        if self.n >= 5 {
+            return self.heights[2];
+        }
+        if self.n == 0 {
+            return f64::NAN;
+        }
+        let mut seen = self.heights[..self.n].to_vec();
+        seen.sort_by(f64::total_cmp);
+        sorted_quantile(&seen, self.p)
+    }
+
+    /// Piecewise-parabolic (P²) prediction for marker `i` moved by `d = +/-1`.
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    /// Linear prediction for marker `i` moved by `d = +/-1`, used when the
+    /// parabola would break the ordering of the markers.
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let j = if d > 0.0 { i + 1 } else { i - 1 };
+        self.heights[i]
+            + d * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+}
+
+/// A merging t-digest (Dunning and Ertl, 2019): a mergeable sketch of a whole
+/// distribution from which any quantile can be queried.
+///
+/// Values are clustered into weighted centroids whose size is limited by the
+/// `k1` scale function `k(q) = compression / (2 pi) * asin(2q - 1)`, so that
+/// centroids stay small in the tails and quantile errors are relative to
+/// `q (1 - q)`. Memory is bounded by a small multiple of `compression`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<f64>,
+    weight: f64,
+    min: f64,
+    max: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+impl Default for TDigest {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMPRESSION)
+    }
+}
+
+impl TDigest {
+    /// An empty digest with the given compression.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `compression` is not a finite number of at least 1.
+    pub fn new(compression: f64) -> Self {
+        assert!(
+            compression.is_finite() && compression >= 1.0,
+            "t-digest compression must be at least 1, got {compression}"
+        );
+        Self {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            weight: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// The number of values pushed.
+    pub fn len(&self) -> usize {
+        (self.weight + self.buffer.len() as f64) as usize
+    }
+
+    /// Whether no value has been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds one value.
+    pub fn push(&mut self, x: f64) {
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.buffer.push(x);
+        if self.buffer.len() >= 5 * self.compression as usize {
+            self.centroids = self.merged();
+            self.weight = self.centroids.iter().map(|c| c.weight).sum();
+            self.buffer.clear();
+        }
+    }
+
+    /// Adds every value of `other`.
+    pub fn merge(&mut self, other: &TDigest) {
+        self.buffer.extend(&other.buffer);
+        let mut all = std::mem::take(&mut self.centroids);
+        all.extend(&other.centroids);
+        all.extend(
+            self.buffer
+                .drain(..)
+                .map(|mean| Centroid { mean, weight: 1.0 }),
+        );
+        self.centroids = self.compress(all);
+        self.weight = self.centroids.iter().map(|c| c.weight).sum();
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// The estimated `q`-quantile, NaN if empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `q` is not in `[0, 1]`.
+    pub fn quantile(&self, q: f64) -> f64 {
+        assert!(
+            (0.0..=1.0).contains(&q),
+            "quantile level {q} is not in [0, 1]"
+        );
+        let centroids = self.merged();
+        let Some((first, last)) = centroids.first().zip(centroids.last()) else {
+            return f64::NAN;
+        };
+        let total: f64 = centroids.iter().map(|c| c.weight).sum();
+        let target = q * total;
+
+        // Each centroid's mass is centred on its mean; interpolate linearly
+        // between neighbouring centres and towards the extremes at the ends.
+        if target <= first.weight / 2.0 {
+            return self.min + (first.mean - self.min) * target / (first.weight / 2.0);
+        }
+        if target >= total - last.weight / 2.0 {
+            let tail = total - target;
+            return self.max - (self.max - last.mean) * tail / (last.weight / 2.0);
+        }
+        let mut centre = first.weight / 2.0;
+        for pair in centroids.windows(2) {
+            let next = centre + (pair[0].weight + pair[1].weight) / 2.0;
+            if target <= next {
+                let fraction = (target - centre) / (next - centre);
+                return pair[0].mean + fraction * (pair[1].mean - pair[0].mean);
+            }
+            centre = next;
+        }
+        last.mean
+    }
+
+    /// The centroids with the buffered values merged in.
+    fn merged(&self) -> Vec<Centroid> {
+        let mut all = self.centroids.clone();
+        all.extend(
+            self.buffer
+                .iter()
+                .map(|&mean| Centroid { mean, weight: 1.0 }),
+        );
+        self.compress(all)
+    }
+
+    /// Sorts the centroids and greedily merges neighbours while the merged
+    /// centroid spans at most one unit of the scale function.
+    fn compress(&self, mut all: Vec<Centroid>) -> Vec<Centroid> {
+        if all.is_empty() {
+            return all;
+        }
+        all.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+        let total: f64 = all.iter().map(|c| c.weight).sum();
+        let delta = self.compression;
+        let k = |q: f64| delta / (2.0 * PI) * (2.0 * q - 1.0).asin();
+        let k_inverse = |k: f64| ((2.0 * PI * k / delta).min(PI / 2.0).sin() + 1.0) / 2.0;
+
+        let mut merged = Vec::with_capacity(all.len().min(2 * delta as usize));
+        let mut current = all[0];
+        let mut before = 0.0;
+        let mut limit = k_inverse(k(0.0) + 1.0);
+        for &next in &all[1..] {
+            if (before + current.weight + next.weight) / total <= limit {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                before += current.weight;
+                merged.push(current);
+                limit = k_inverse(k(before / total) + 1.0);
+                current = next;
+            }
+        }
+        merged.push(current);
+        merged
+    }
+}
+
+impl Extend<f64> for TDigest {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+/// The distribution of the simulated values at one time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepSummary {
+    /// The time of the cross-section.
+    pub time: f64,
+    /// Mean, variance and range of the values.
+    pub stats: RunningStats,
+    /// Sketch of the distribution of the values, for quantiles.
+    pub digest: TDigest,
+    /// Mean and variance of the antithetic pair averages, when the paths come
+    /// in antithetic pairs.
+    pub pairs: Option<RunningStats>,
+}
+
+impl StepSummary {
+    fn new(time: f64, compression: f64) -> Self {
+        Self {
+            time,
+            stats: RunningStats::new(),
+            digest: TDigest::new(compression),
+            pairs: None,
+        }
+    }
+
+    fn push(&mut self, x: f64) {
+        self.stats.push(x);
+        self.digest.push(x);
+    }
+
+    /// Adds the average of an antithetic pair whose values were pushed.
+    fn push_pair_mean(&mut self, a: f64, b: f64) {
+        self.pairs
+            .get_or_insert_with(RunningStats::new)
+            .push(0.5 * (a + b));
+    }
+
+    /// The sample mean.
+    pub fn mean(&self) -> f64 {
+        self.stats.mean()
+    }
+
+    /// The sample variance.
+    pub fn variance(&self) -> f64 {
+        self.stats.variance()
+    }
+
+    /// The asymptotic 95% confidence interval for the mean. Antithetic pairs
+    /// are not independent, so with them the standard error comes from the
+    /// pair averages, as in [`PathMatrix::estimate`].
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        match &self.pairs {
+            Some(pairs) => Estimate {
+                mean: self.mean(),
+                ..pairs.estimate()
+            }
+            .confidence_interval(),
+            None => self.stats.estimate().confidence_interval(),
+        }
+    }
+
+    /// The estimated `q`-quantile of the values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `q` is not in `[0, 1]`.
+    pub fn quantile(&self, q: f64) -> f64 {
+        self.digest.quantile(q)
+    }
+}
+
+/// Per-time-step distributions of simulated paths, filled either path by path
+/// or one cross-section at a time.
+///
+/// With [`PathStatistics::with_antithetic`], consecutive paths are treated as
+/// antithetic pairs and confidence intervals account for the pairing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathStatistics {
+    steps: Vec<StepSummary>,
+    antithetic: bool,
+    /// The first path of an antithetic pair pushed with `push_path`.
+    pending: Option<Vec<f64>>,
+}
+
+impl PathStatistics {
+    /// Empty statistics on the time grid of the paths to come.
+    pub fn new(times: &[f64]) -> Self {
+        Self::with_compression(times, DEFAULT_COMPRESSION)
+    }
+
+    /// Empty statistics whose quantile sketches use the given t-digest
+    /// compression.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `compression` is less than 1 (see [`TDigest::new`]).
+    pub fn with_compression(times: &[f64], compression: f64) -> Self {
+        Self {
+            steps: times
+                .iter()
+                .map(|&t| StepSummary::new(t, compression))
+                .collect(),
+            antithetic: false,
+            pending: None,
+        }
+    }
+
+    /// Treats paths `2k` and `2k + 1` as an antithetic pair, as in
+    /// [`PathMatrix::is_antithetic`], so that confidence intervals use the
+    /// pair averages.
+    pub fn with_antithetic(mut self, antithetic: bool) -> Self {
+        self.antithetic = antithetic;
+        self
+    }
+
+    /// The statistics of every path in `paths`, paired if the matrix is
+    /// antithetic.
+    pub fn from_paths(paths: &PathMatrix) -> Self {
+        let mut stats = Self::new(paths.times()).with_antithetic(paths.is_antithetic());
+        for path in paths {
+            stats.push_path(path);
+        }
+        stats
+    }
+
+    /// Adds one path, with one value per time step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path does not have one value per time step.
+    pub fn push_path(&mut self, path: &[f64]) {
+        assert_eq!(
+            path.len(),
+            self.steps.len(),
+            "path length does not match the number of time steps"
+        );
+        for (step, &x) in self.steps.iter_mut().zip(path) {
+            step.push(x);
+        }
+        if !self.antithetic {
+            return;
+        }
+        match self.pending.take() {
+            Some(first) => {
+                for ((step, &a), &b) in self.steps.iter_mut().zip(&first).zip(path) {
+                    step.push_pair_mean(a, b);
+                }
+            }
+            None => self.pending = Some(path.to_vec()),
+        }
+    }
+
+    /// Adds the values of several paths at time step `j`. With antithetic
+    /// pairs, `values` holds whole pairs, as the cross-sections of
+    /// [`StepIter`](crate::StepIter) do.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `j` is out of range, or if the paths are antithetic and
+    /// `values` has an odd length.
+    pub fn push_step(&mut self, j: usize, values: &[f64]) {
+        let step = &mut self.steps[j];
+        for &x in values {
+            step.push(x);
+        }
+        if self.antithetic {
+            assert!(
+                values.len().is_multiple_of(2),
+                "antithetic values must come in pairs, got {}",
+                values.len()
+            );
+            for pair in values.chunks_exact(2) {
+                step.push_pair_mean(pair[0], pair[1]);
+            }
+        }
+    }
+
+    /// The number of time steps, including `t = 0`.
+    pub fn n_times(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// The summaries of all time steps, in time order.
+    pub fn steps(&self) -> &[StepSummary] {
+        &self.steps
+    }
+
+    /// The summary of time step `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `j` is out of range.
+    pub fn step(&self, j: usize) -> &StepSummary {
+        &self.steps[j]
+    }
+
+    /// The summary of the last time step.
+    pub fn terminal(&self) -> &StepSummary {
+        self.steps.last().expect("statistics have no time steps")
+    }
+}
+
+/// The linearly interpolated `p`-quantile of sorted values.
+fn sorted_quantile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p * (sorted.len() - 1) as f64;
+    let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lo] + (rank - lo as f64) * (sorted[hi] - sorted[lo])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{engine, normal, ArithmeticBrownianMotion};
+    use rand::Rng;
+    use rand_distr::StandardNormal;
+
+    fn normal_samples(n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = engine::path_rng(seed, 0);
+        (0..n).map(|_| rng.sample(StandardNormal)).collect()
+    }
+
+    #[test]
+    fn test_running_stats_matches_two_pass() {
+        // A large offset would ruin the naive sum-of-squares formula.
+        let values: Vec<f64> = normal_samples(1000, 1).iter().map(|x| 1e9 + x).collect();
+        let centred: Vec<f64> = values.iter().map(|x| x - 1e9).collect();
+        let estimate = Estimate::from_samples(&centred);
+        let stats: RunningStats = values.iter().copied().collect();
+        assert!((stats.mean() - 1e9 - estimate.mean).abs() < 1e-6);
+        assert!((stats.estimate().std_error - estimate.std_error).abs() < 1e-9);
+
+        let (left, right) = values.split_at(300);
+        let mut merged: RunningStats = left.iter().copied().collect();
+        merged.merge(&right.iter().copied().collect());
+        assert_eq!(merged.len(), 1000);
+        assert!((merged.mean() - stats.mean()).abs() < 1e-6);
+        assert!((merged.variance() - stats.variance()).abs() < 1e-6);
+        assert_eq!((merged.min(), merged.max()), (stats.min(), stats.max()));
+    }
+
+    #[test]
+    fn test_p2_quantile() {
+        let values = normal_samples(100_000, 2);
+        for p in [0.05, 0.5, 0.9] {
+            let mut tracker = P2Quantile::new(p);
+            values.iter().for_each(|&x| tracker.push(x));
+            let expected = normal::inverse_cdf(p);
+            assert!(
+                (tracker.quantile() - expected).abs() < 0.02,
+                "p = {p}: {} vs {expected}",
+                tracker.quantile()
+            );
+        }
+
+        let mut small = P2Quantile::new(0.5);
+        [3.0, 1.0, 2.0].iter().for_each(|&x| small.push(x));
+        assert_eq!(small.quantile(), 2.0);
+    }
+
+    #[test]
+    fn test_tdigest_quantiles() {
+        let values = normal_samples(100_000, 3);
+        let (left, right) = values.split_at(40_000);
+        let mut digest = TDigest::default();
+        digest.extend(left.iter().copied());
+        let mut other = TDigest::default();
+        other.extend(right.iter().copied());
+        digest.merge(&other);
+        assert_eq!(digest.len(), 100_000);
+
+        for q in [0.001, 0.05, 0.25, 0.5, 0.75, 0.95, 0.999] {
+            let expected = normal::inverse_cdf(q);
+            let got = digest.quantile(q);
+            assert!(
+                (got - expected).abs() < 0.03,
+                "q = {q}: {got} vs {expected}"
+            );
+        }
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        assert_eq!(digest.quantile(0.0), min);
+        assert!(digest.centroids.len() < 2 * DEFAULT_COMPRESSION as usize);
+    }
+
+    #[test]
+    fn test_path_statistics_streaming_matches_matrix() {
+        let abm = ArithmeticBrownianMotion::new(0.5, 0.8, 4000, 8, 2.0, 1.0).with_seed(4);
+        let from_matrix = PathStatistics::from_paths(&abm.simulate());
+
+        let mut from_steps = PathStatistics::new(&abm.time_points());
+        for (j, (_, values)) in abm.steps().enumerate() {
+            from_steps.push_step(j, &values);
+        }
+        assert_eq!(from_steps, from_matrix);
+
+        let terminal = from_matrix.terminal();
+        assert_eq!(terminal.time, 2.0);
+        let (lo, hi) = terminal.confidence_interval();
+        assert!(lo < 2.0 && 2.0 < hi, "({lo}, {hi})");
+        let sd = 0.8 * 2.0_f64.sqrt();
+        assert!((terminal.quantile(0.9) - (2.0 + sd * normal::inverse_cdf(0.9))).abs() < 0.1);
+        assert_eq!(from_matrix.step(0).variance(), 0.0);
+    }
+
+    #[test]
+    fn test_path_statistics_antithetic() {
+        let abm = ArithmeticBrownianMotion::builder()
+            .mu(0.5)
+            .sigma(0.8)
+            .n_paths(4000)
+            .n_steps(8)
+            .antithetic(true)
+            .seed(5)
+            .build()
+            .unwrap();
+        let paths = abm.simulate();
+        let from_matrix = PathStatistics::from_paths(&paths);
+
+        let mut from_steps = PathStatistics::new(&abm.time_points()).with_antithetic(true);
+        for (j, (_, values)) in abm.steps().enumerate() {
+            from_steps.push_step(j, &values);
+        }
+        assert_eq!(from_steps.terminal().pairs, from_matrix.terminal().pairs);
+
+        // The interval of the pair averages, not of 4000 independent values.
+        for j in [4, 8] {
+            let expected = paths.estimate(|p| p[j]).confidence_interval();
+            let (lo, hi) = from_matrix.step(j).confidence_interval();
+            assert!((lo - expected.0).abs() < 1e-12 && (hi - expected.1).abs() < 1e-12);
+            let naive = from_matrix.step(j).stats.estimate().confidence_interval();
+            assert!(hi - lo < 0.01 * (naive.1 - naive.0));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Iterators that generate simulations incrementally, so that memory does not
+//! grow with the product of paths and steps.
+//!
+//! Both iterators use the per-path random streams of `simulate`, so for a
+//! given seed they reproduce its paths exactly.
+
+use crate::abm::{advance, fill_antithetic_pair, fill_path};
+use crate::engine;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use rand_distr::StandardNormal;
+
+/// Paths generated one at a time, returned by
+/// [`ArithmeticBrownianMotion::paths`](crate::ArithmeticBrownianMotion::paths).
+///
+/// Holds a single path (two in antithetic mode) at any time.
+#[derive(Debug, Clone)]
+pub struct PathIter {
+    increments: Vec<(f64, f64)>,
+    s_0: f64,
+    seed: u64,
+    n_paths: usize,
+    antithetic: bool,
+    index: usize,
+    mirror: Option<Vec<f64>>,
+}
+
+impl PathIter {
+    /// Paths from `s_0` advancing by the Gaussian `increments` of each step.
+    pub(crate) fn new(
+        increments: Vec<(f64, f64)>,
+        s_0: f64,
+        seed: u64,
+        n_paths: usize,
+        antithetic: bool,
+    ) -> Self {
+        Self {
+            increments,
+            s_0,
+            seed,
+            n_paths,
+            antithetic,
+            index: 0,
+            mirror: None,
+        }
+    }
+}
+
+impl Iterator for PathIter {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.index >= self.n_paths {
+            return None;
+        }
+        self.index += 1;
+        if let Some(mirror) = self.mirror.take() {
+            return Some(mirror);
+        }
+
+        let n_times = self.increments.len() + 1;
+        if self.antithetic {
+            let mut rng = engine::path_rng(self.seed, (self.index - 1) / 2);
+            let mut pair = vec![0.0; 2 * n_times];
+            fill_antithetic_pair(&mut pair, self.s_0, &self.increments, &mut rng);
+            self.mirror = Some(pair.split_off(n_times));
+            return Some(pair);
+        }
+        let mut rng = engine::path_rng(self.seed, self.index - 1);
+        let mut path = vec![0.0; n_times];
+        fill_path(&mut path, self.s_0, &self.increments, &mut rng);
+        Some(path)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.n_paths - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PathIter {}
+
+/// Cross-sections of all paths, one time step at a time, returned by
+/// [`ArithmeticBrownianMotion::steps`](crate::ArithmeticBrownianMotion::steps).
+///
+/// Each item is `(t, values)` with the value of every path at time `t`,
+/// starting with `t = 0`. Holds one value and one generator per path.
+#[derive(Debug, Clone)]
+pub struct StepIter {
+    times: Vec<f64>,
+    increments: Vec<(f64, f64)>,
+    values: Vec<f64>,
+    rngs: Vec<ChaCha8Rng>,
+    antithetic: bool,
+    step: usize,
+}
+
+impl StepIter {
+    /// Cross-sections on `times`, advancing by the Gaussian `increments` of
+    /// each step.
+    pub(crate) fn new(
+        times: Vec<f64>,
+        increments: Vec<(f64, f64)>,
+        s_0: f64,
+        seed: u64,
+        n_paths: usize,
+        antithetic: bool,
+    ) -> Self {
+        let n_streams = if antithetic { n_paths / 2 } else { n_paths };
+        Self {
+            times,
+            increments,
+            values: vec![s_0; n_paths],
+            rngs: (0..n_streams).map(|i| engine::path_rng(seed, i)).collect(),
+            antithetic,
+            step: 0,
+        }
+    }
+}
+
+impl Iterator for StepIter {
+    type Item = (f64, Vec<f64>);
+
+    fn next(&mut self) -> Option<(f64, Vec<f64>)> {
+        if self.step >= self.times.len() {
+            return None;
+        }
+        if self.step > 0 {
+            let increment = self.increments[self.step - 1];
+            let group = if self.antithetic { 2 } else { 1 };
+            for (values, rng) in self.values.chunks_exact_mut(group).zip(&mut self.rngs) {
+                let z: f64 = rng.sample(StandardNormal);
+                values[0] = advance(values[0], increment, z);
+                if let Some(mirror) = values.get_mut(1) {
+                    *mirror = advance(*mirror, increment, -z);
+                }
+            }
+        }
+        self.step += 1;
+        Some((self.times[self.step - 1], self.values.clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.times.len() - self.step;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for StepIter {}
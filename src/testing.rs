@@ -0,0 +1,9 @@
+//! Helpers shared by the unit tests.
+
+/// The sample mean and unbiased sample variance of `values`.
+pub(crate) fn mean_and_variance(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let var = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, var)
+}